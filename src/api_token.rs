@@ -0,0 +1,95 @@
+use anyhow::Result;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use std::io::{Read, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use crate::config::get_data_dir;
+
+pub fn token_path() -> PathBuf {
+    get_data_dir().join("api-token")
+}
+
+fn generate_token() -> Result<String> {
+    let mut bytes = [0u8; 32];
+    std::fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Returns the token stored at `path`, creating it (readable only by the
+/// current user) if there isn't one yet.
+pub fn load_or_create(path: &Path) -> Result<String> {
+    if let Ok(existing) = std::fs::read_to_string(path) {
+        let existing = existing.trim().to_string();
+        if !existing.is_empty() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+            return Ok(existing);
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let token = generate_token()?;
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+/// The token clients send to the daemon, if the daemon has created one.
+pub fn read_token() -> Option<String> {
+    std::fs::read_to_string(token_path())
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+pub fn auth_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(value) =
+        read_token().and_then(|t| HeaderValue::from_str(&format!("Bearer {}", t)).ok())
+    {
+        headers.insert(AUTHORIZATION, value);
+    }
+    headers
+}
+
+/// HTTP client for talking to the daemon, with the token attached.
+pub fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .default_headers(auth_headers())
+        .build()
+        .unwrap_or_default()
+}
+
+pub fn blocking_client() -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .default_headers(auth_headers())
+        .build()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_created_private_and_reused() {
+        let dir = std::env::temp_dir().join(format!("sp-token-{}", std::process::id()));
+        let path = dir.join("api-token");
+
+        let token = load_or_create(&path).unwrap();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        assert_eq!(load_or_create(&path).unwrap(), token);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,286 @@
+use anyhow::Result;
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::get_data_dir;
+use crate::db::{backup_connection, integrity_problems, Database, BUSY_TIMEOUT};
+
+pub const BACKUP_INTERVAL_SECS: u64 = 3600;
+pub const BACKUPS_TO_KEEP: usize = 5;
+
+const BACKUP_PREFIX: &str = "state-";
+const BACKUP_SUFFIX: &str = ".db";
+
+pub fn db_path() -> PathBuf {
+    get_data_dir().join("state.db")
+}
+
+pub fn backup_dir() -> PathBuf {
+    get_data_dir().join("backups")
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupCheck {
+    #[default]
+    Ok,
+    RestoredFromBackup,
+    StartedFresh,
+}
+
+/// What the daemon found when it opened the database, plus backup activity
+/// since then. Reported through `/status`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DbHealth {
+    pub startup: StartupCheck,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quarantined: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restored_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_backup: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_backup_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_backup_error: Option<String>,
+}
+
+/// Opens `path` read-only and runs an integrity check. Files that aren't
+/// SQLite databases at all surface as an error rather than a problem list.
+pub fn check_file(path: &Path) -> Result<Vec<String>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    integrity_problems(&conn)
+}
+
+/// Whether a `check_file` error means the file itself is damaged, as
+/// opposed to being locked or unreadable.
+fn is_corruption(e: &anyhow::Error) -> bool {
+    use rusqlite::ErrorCode;
+    e.downcast_ref::<rusqlite::Error>()
+        .and_then(|e| e.sqlite_error_code())
+        .is_some_and(|code| matches!(code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase))
+}
+
+fn timestamp_suffix() -> String {
+    chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string()
+}
+
+fn sidecar_paths(path: &Path) -> Vec<PathBuf> {
+    ["-journal", "-wal", "-shm"]
+        .iter()
+        .map(|suffix| {
+            let mut name = path.as_os_str().to_owned();
+            name.push(suffix);
+            PathBuf::from(name)
+        })
+        .collect()
+}
+
+/// Moves a database (and any journal files next to it) aside, returning the
+/// new location of the main file.
+pub fn move_aside(path: &Path, label: &str) -> Result<PathBuf> {
+    let suffix = format!(".{}-{}", label, timestamp_suffix());
+    let mut target = path.as_os_str().to_owned();
+    target.push(&suffix);
+    let target = PathBuf::from(target);
+    std::fs::rename(path, &target)?;
+
+    for sidecar in sidecar_paths(path) {
+        if sidecar.exists() {
+            let mut moved = sidecar.as_os_str().to_owned();
+            moved.push(&suffix);
+            let _ = std::fs::rename(&sidecar, PathBuf::from(moved));
+        }
+    }
+    Ok(target)
+}
+
+/// Lists backups in `dir`, newest first.
+pub fn list_backups(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name().is_some_and(|n| {
+                let n = n.to_string_lossy();
+                n.starts_with(BACKUP_PREFIX) && n.ends_with(BACKUP_SUFFIX)
+            })
+        })
+        .collect();
+    backups.sort();
+    backups.reverse();
+    backups
+}
+
+/// How long until the next scheduled backup is due: an `interval` after the
+/// newest backup in `dir`, or now if there is none.
+pub fn next_backup_in(dir: &Path, interval: Duration) -> Duration {
+    list_backups(dir)
+        .first()
+        .and_then(|newest| std::fs::metadata(newest).ok()?.modified().ok())
+        .and_then(|modified| modified.elapsed().ok())
+        .map_or(Duration::ZERO, |age| interval.saturating_sub(age))
+}
+
+/// Opens the database at `path` read-only on a connection of its own, so a
+/// backup from it doesn't hold up the daemon's connection.
+pub fn open_backup_source(path: &Path) -> Result<Connection> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    Ok(conn)
+}
+
+/// Writes a timestamped backup of the database open on `source` into `dir`
+/// and prunes all but the newest `keep` backups.
+pub fn create_backup(source: &Connection, dir: &Path, keep: usize) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let name = format!("{}{}{}", BACKUP_PREFIX, timestamp_suffix(), BACKUP_SUFFIX);
+    let path = dir.join(&name);
+    let partial = dir.join(format!(".{}.partial", name));
+
+    backup_connection(source, &partial)?;
+    std::fs::rename(&partial, &path)?;
+
+    for old in list_backups(dir).into_iter().skip(keep) {
+        let _ = std::fs::remove_file(old);
+    }
+    Ok(path)
+}
+
+/// Opens the database at `path`, checking its integrity first. A corrupt
+/// file is quarantined and replaced with the newest backup that passes the
+/// same check, or with an empty database if there is none. Any other error
+/// (a locked or unreadable file) is returned and the file left alone.
+pub fn open_with_recovery(path: &Path, backups: &Path) -> Result<(Database, DbHealth)> {
+    let mut health = DbHealth::default();
+
+    if path.exists() {
+        let problems = match check_file(path) {
+            Ok(problems) => problems,
+            Err(e) if is_corruption(&e) => vec![e.to_string()],
+            Err(e) => return Err(e.context(format!("Can't check {}", path.display()))),
+        };
+
+        if !problems.is_empty() {
+            health.problems = problems;
+            health.quarantined = Some(move_aside(path, "corrupt")?.display().to_string());
+            health.startup = StartupCheck::StartedFresh;
+
+            for backup in list_backups(backups) {
+                if matches!(check_file(&backup), Ok(p) if p.is_empty()) {
+                    std::fs::copy(&backup, path)?;
+                    health.restored_from = Some(backup.display().to_string());
+                    health.startup = StartupCheck::RestoredFromBackup;
+                    break;
+                }
+            }
+        }
+    }
+
+    let db = Database::open(path)?;
+    Ok((db, health))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn seed(db: &Database, session_id: &str) {
+        db.register_placeholder(session_id, "2026-01-01T00:00:00+00:00")
+            .unwrap();
+    }
+
+    #[test]
+    fn healthy_database_opens_untouched() {
+        let dir = TempDir::new("backup");
+        let path = dir.0.join("state.db");
+        seed(&Database::open(&path).unwrap(), "/work/api");
+
+        let (db, health) = open_with_recovery(&path, &dir.0.join("backups")).unwrap();
+        assert_eq!(health.startup, StartupCheck::Ok);
+        assert_eq!(db.get_plates().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn backups_rotate_keeping_the_newest() {
+        let dir = TempDir::new("backup");
+        let db = Database::open(&dir.0.join("state.db")).unwrap();
+        let backups = dir.0.join("backups");
+
+        let mut created = Vec::new();
+        for _ in 0..4 {
+            created.push(create_backup(db.conn(), &backups, 2).unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        let remaining = list_backups(&backups);
+        assert_eq!(remaining, vec![created[3].clone(), created[2].clone()]);
+        assert!(check_file(&remaining[0]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn next_backup_waits_out_the_interval_since_the_newest() {
+        let dir = TempDir::new("backup");
+        let backups = dir.0.join("backups");
+        let hour = Duration::from_secs(3600);
+        assert_eq!(next_backup_in(&backups, hour), Duration::ZERO);
+
+        let db = Database::open(&dir.0.join("state.db")).unwrap();
+        create_backup(db.conn(), &backups, BACKUPS_TO_KEEP).unwrap();
+        let wait = next_backup_in(&backups, hour);
+        assert!(wait > Duration::from_secs(3500) && wait <= hour);
+    }
+
+    #[test]
+    fn corrupt_database_is_quarantined_and_restored_from_backup() {
+        let dir = TempDir::new("backup");
+        let path = dir.0.join("state.db");
+        let backups = dir.0.join("backups");
+        {
+            let db = Database::open(&path).unwrap();
+            seed(&db, "/work/api");
+            create_backup(db.conn(), &backups, BACKUPS_TO_KEEP).unwrap();
+        }
+        std::fs::write(&path, b"definitely not sqlite, just garbage bytes").unwrap();
+
+        let (db, health) = open_with_recovery(&path, &backups).unwrap();
+        assert_eq!(health.startup, StartupCheck::RestoredFromBackup);
+        assert!(!health.problems.is_empty());
+        assert!(Path::new(health.quarantined.as_ref().unwrap()).exists());
+        assert_eq!(db.get_plates().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn unreadable_database_is_left_alone() {
+        let dir = TempDir::new("backup");
+        // A directory where the database should be can't be opened, but
+        // isn't corrupt either
+        let path = dir.0.join("state.db");
+        std::fs::create_dir_all(&path).unwrap();
+
+        assert!(open_with_recovery(&path, &dir.0.join("backups")).is_err());
+        assert!(path.is_dir());
+        assert_eq!(std::fs::read_dir(&dir.0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn corrupt_database_without_backup_starts_fresh() {
+        let dir = TempDir::new("backup");
+        let path = dir.0.join("state.db");
+        std::fs::write(&path, b"garbage").unwrap();
+
+        let (db, health) = open_with_recovery(&path, &dir.0.join("backups")).unwrap();
+        assert_eq!(health.startup, StartupCheck::StartedFresh);
+        assert!(health.restored_from.is_none());
+        assert!(db.get_plates().unwrap().is_empty());
+    }
+}
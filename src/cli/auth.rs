@@ -1,18 +1,40 @@
-use crate::config::{delete_auth_config, get_auth_config_path, save_auth_config, AuthConfig};
+use crate::config::{
+    delete_auth_config, get_auth_config_path, load_config, save_auth_config, AuthConfig,
+    SummarizerBackend,
+};
+use crate::daemon::summarizer::{get_api_key, verify_anthropic_key};
 use anyhow::Result;
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use std::io::{self, Read, Write};
 
 pub fn auth_status() -> Result<()> {
     let path = get_auth_config_path();
-    if path.exists() {
-        println!("API key configured at {}", path.display());
-    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         println!("Using ANTHROPIC_API_KEY from environment");
+    } else if path.exists() {
+        println!("API key configured at {}", path.display());
     } else {
         println!("No API key configured");
         println!("Run `sp auth set` to configure");
     }
+
+    let mut config = load_config().summarizer;
+    if config.backend != SummarizerBackend::Anthropic {
+        println!(
+            "Summaries use the {} backend, which doesn't need this key",
+            serde_json::to_value(config.backend)?
+                .as_str()
+                .unwrap_or("?")
+        );
+        // base_url points at that backend, not at Anthropic
+        config.base_url = None;
+    }
+    if let Some(key) = get_api_key() {
+        match verify_anthropic_key(&config, &key) {
+            Ok(()) => println!("Key works"),
+            Err(failure) => println!("Key check failed: {}", failure),
+        }
+    }
     Ok(())
 }
 
@@ -55,9 +77,7 @@ fn read_masked_input_inner() -> Result<String> {
         match buf[0] {
             b'\n' | b'\r' => break,
             127 | 8 => {
-                if !input.is_empty() {
-                    input.pop();
-                }
+                input.pop();
             }
             3 => anyhow::bail!("Cancelled"),
             c if c >= 32 => {
@@ -0,0 +1,138 @@
+use anyhow::{bail, Result};
+
+use crate::hook::DAEMON_URL;
+use crate::models::{local_time, Digest, Plate, PlateStatus};
+
+/// Finds the plate meant by `query`: a session id, a prefix of one, or a
+/// project name, optionally with `/branch`. Open plates win over closed
+/// ones when a project has both.
+pub fn resolve_plate<'a>(plates: &'a [Plate], query: &str) -> Result<&'a Plate> {
+    if let Some(plate) = plates.iter().find(|p| p.session_id == query) {
+        return Ok(plate);
+    }
+
+    let mut matches: Vec<&Plate> = plates
+        .iter()
+        .filter(|p| p.host.is_none())
+        .filter(|p| {
+            p.session_id.starts_with(query)
+                || p.in_project(query)
+                || p.git_branch
+                    .as_ref()
+                    .is_some_and(|b| format!("{}/{}", p.project_name(), b) == query)
+        })
+        .collect();
+    if matches.iter().any(|p| p.status != PlateStatus::Closed) {
+        matches.retain(|p| p.status != PlateStatus::Closed);
+    }
+
+    match matches.as_slice() {
+        [] => bail!("No plate matches \"{}\"", query),
+        [plate] => Ok(plate),
+        _ => {
+            let ids: Vec<_> = matches
+                .iter()
+                .map(|p| format!("  {}  {}", p.session_id, label(p)))
+                .collect();
+            bail!("\"{}\" matches several plates:\n{}", query, ids.join("\n"))
+        }
+    }
+}
+
+fn label(plate: &Plate) -> String {
+    match &plate.git_branch {
+        Some(branch) => format!("{}/{}", plate.project_name(), branch),
+        None => plate.project_name().to_string(),
+    }
+}
+
+/// Prints what a plate did since it was last acknowledged, then
+/// acknowledges it unless `keep` is set.
+pub fn catchup(query: &str, json: bool, keep: bool) -> Result<()> {
+    let client = crate::api_token::blocking_client();
+    let timeout = std::time::Duration::from_secs(5);
+    let plates: Vec<Plate> = client
+        .get(format!("{}/plates", DAEMON_URL))
+        .timeout(timeout)
+        .send()?
+        .json()?;
+    let plate = resolve_plate(&plates, query)?;
+
+    let digest: Digest = client
+        .get(format!("{}/plates/{}/digest", DAEMON_URL, plate.session_id))
+        .timeout(timeout)
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&digest)?);
+    } else {
+        let since = digest
+            .since
+            .as_deref()
+            .map(|at| format!("since {}", local_time(at)))
+            .unwrap_or_else(|| "since it started".to_string());
+        if digest.entries.is_empty() {
+            println!("Nothing new on {} {}", label(plate), since);
+        } else {
+            println!("{} {}", label(plate), since);
+            for entry in &digest.entries {
+                println!("  {}", entry);
+            }
+        }
+    }
+
+    if !keep {
+        client
+            .post(format!("{}/plates/{}/ack", DAEMON_URL, plate.session_id))
+            .timeout(timeout)
+            .send()?
+            .error_for_status()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plate(session_id: &str, project_path: &str, status: PlateStatus) -> Plate {
+        Plate {
+            git_branch: Some("main".to_string()),
+            ..Plate::for_test(session_id, project_path, status)
+        }
+    }
+
+    #[test]
+    fn resolves_ids_prefixes_and_projects() {
+        let plates = vec![
+            plate("abc123", "/work/api", PlateStatus::Running),
+            plate("abd456", "/work/api", PlateStatus::Closed),
+            plate("ffe789", "/work/web", PlateStatus::Idle),
+        ];
+        assert_eq!(
+            resolve_plate(&plates, "abd456").unwrap().session_id,
+            "abd456"
+        );
+        assert_eq!(resolve_plate(&plates, "ff").unwrap().session_id, "ffe789");
+        // The open plate wins over the closed one
+        assert_eq!(resolve_plate(&plates, "api").unwrap().session_id, "abc123");
+        assert_eq!(
+            resolve_plate(&plates, "web/main").unwrap().session_id,
+            "ffe789"
+        );
+        assert!(resolve_plate(&plates, "ab").is_ok());
+        assert!(resolve_plate(&plates, "nope").is_err());
+    }
+
+    #[test]
+    fn ambiguous_queries_list_candidates() {
+        let plates = vec![
+            plate("abc123", "/work/api", PlateStatus::Running),
+            plate("abd456", "/work/api", PlateStatus::Idle),
+        ];
+        let err = resolve_plate(&plates, "api").unwrap_err().to_string();
+        assert!(err.contains("abc123") && err.contains("abd456"));
+    }
+}
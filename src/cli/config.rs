@@ -48,8 +48,22 @@ pub fn config_set(key: &str, value: &str) -> Result<()> {
                 _ => anyhow::bail!("Invalid value for sounds.enabled: use true/false"),
             };
         }
+        "allow_remote_actions" => {
+            config.allow_remote_actions = match value.to_lowercase().as_str() {
+                "true" | "1" | "on" | "yes" => true,
+                "false" | "0" | "off" | "no" => false,
+                _ => anyhow::bail!("Invalid value for allow_remote_actions: use true/false"),
+            };
+        }
+        "allowed_origins" => {
+            config.allowed_origins = value
+                .split(',')
+                .map(|o| o.trim().to_string())
+                .filter(|o| !o.is_empty())
+                .collect();
+        }
         _ => anyhow::bail!(
-            "Unknown config key: {}\nAvailable keys: tmux_mode, sounds.enabled",
+            "Unknown config key: {}\nAvailable keys: tmux_mode, sounds.enabled, allowed_origins, allow_remote_actions",
             key
         ),
     }
@@ -0,0 +1,52 @@
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+
+use crate::backup::{
+    backup_dir, check_file, create_backup, db_path, list_backups, move_aside, BACKUPS_TO_KEEP,
+};
+use crate::db::Database;
+
+pub fn db_backup(output: Option<&str>) -> Result<()> {
+    let db = Database::open(&db_path())?;
+    let path = match output {
+        Some(output) => {
+            let path = PathBuf::from(output);
+            db.backup_to(&path)?;
+            path
+        }
+        None => create_backup(db.conn(), &backup_dir(), BACKUPS_TO_KEEP)?,
+    };
+    println!("Backed up database to {}", path.display());
+    Ok(())
+}
+
+pub fn db_restore(file: Option<&str>) -> Result<()> {
+    let source = match file {
+        Some(file) => PathBuf::from(file),
+        None => match list_backups(&backup_dir()).into_iter().next() {
+            Some(latest) => latest,
+            None => bail!("No backups found in {}", backup_dir().display()),
+        },
+    };
+
+    let problems = check_file(&source)?;
+    if !problems.is_empty() {
+        bail!(
+            "{} failed its integrity check:\n  {}",
+            source.display(),
+            problems.join("\n  ")
+        );
+    }
+
+    crate::kill_daemon();
+
+    let path = db_path();
+    if path.exists() {
+        let previous = move_aside(&path, "before-restore")?;
+        println!("Moved current database to {}", previous.display());
+    }
+    std::fs::copy(&source, &path)?;
+    println!("Restored database from {}", source.display());
+    println!("The daemon restarts with the restored data on the next `sp` command");
+    Ok(())
+}
@@ -0,0 +1,480 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+
+use crate::backup::db_path;
+use crate::db::{parse_since, Database, StoredEvent, Transition};
+use crate::models::{Plate, PlateStatus};
+
+/// Bumped whenever a field is renamed or removed. Adding fields does not bump it.
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Jsonl,
+    Csv,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "jsonl" => Ok(Self::Jsonl),
+            "csv" => Ok(Self::Csv),
+            _ => bail!("Unknown export format: {} (use jsonl or csv)", s),
+        }
+    }
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTable {
+    Plates,
+    Transitions,
+    Events,
+}
+
+impl ExportTable {
+    const ALL: [ExportTable; 3] = [Self::Plates, Self::Transitions, Self::Events];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Plates => "plates",
+            Self::Transitions => "transitions",
+            Self::Events => "events",
+        }
+    }
+
+    fn record_name(&self) -> &'static str {
+        match self {
+            Self::Plates => "plate",
+            Self::Transitions => "transition",
+            Self::Events => "event",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportTable {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "plates" => Ok(Self::Plates),
+            "transitions" => Ok(Self::Transitions),
+            "events" => Ok(Self::Events),
+            _ => bail!(
+                "Unknown export table: {} (use plates, transitions or events)",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ExportFilter {
+    pub since: Option<String>,
+    pub project: Option<String>,
+    pub statuses: Vec<PlateStatus>,
+}
+
+impl ExportFilter {
+    pub fn new(since: Option<&str>, project: Option<&str>, status: Option<&str>) -> Result<Self> {
+        let since = since.map(parse_since).transpose()?;
+        let statuses = match status {
+            Some(list) => list
+                .split(',')
+                .map(|s| {
+                    s.trim()
+                        .parse::<PlateStatus>()
+                        .map_err(|_| anyhow::anyhow!("Unknown status: {}", s.trim()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            since,
+            project: project.map(|p| p.trim_end_matches('/').to_string()),
+            statuses,
+        })
+    }
+
+    fn matches_plate(&self, plate: &Plate) -> bool {
+        if !self.statuses.is_empty() && !self.statuses.contains(&plate.status) {
+            return false;
+        }
+        if let Some(project) = &self.project {
+            if !plate.in_project(project) {
+                return false;
+            }
+        }
+        self.is_after_since(&plate.updated_at)
+    }
+
+    fn is_after_since(&self, timestamp: &str) -> bool {
+        match &self.since {
+            Some(since) => timestamp >= since.as_str(),
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlateRecord {
+    pub session_id: String,
+    pub project_path: String,
+    pub project_name: String,
+    pub git_branch: Option<String>,
+    pub status: String,
+    pub goal: Option<String>,
+    pub summary: Option<String>,
+    pub todos_completed: Option<u32>,
+    pub todos_total: Option<u32>,
+    pub transcript_path: Option<String>,
+    pub tmux_target: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransitionRecord {
+    pub id: i64,
+    pub session_id: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventRecord {
+    pub id: i64,
+    pub session_id: String,
+    pub event_type: String,
+    pub created_at: String,
+    pub payload: serde_json::Value,
+}
+
+trait CsvRecord {
+    const HEADER: &'static [&'static str];
+    fn csv_fields(&self) -> Vec<String>;
+}
+
+impl CsvRecord for PlateRecord {
+    const HEADER: &'static [&'static str] = &[
+        "session_id",
+        "project_path",
+        "project_name",
+        "git_branch",
+        "status",
+        "goal",
+        "summary",
+        "todos_completed",
+        "todos_total",
+        "transcript_path",
+        "tmux_target",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.session_id.clone(),
+            self.project_path.clone(),
+            self.project_name.clone(),
+            self.git_branch.clone().unwrap_or_default(),
+            self.status.clone(),
+            self.goal.clone().unwrap_or_default(),
+            self.summary.clone().unwrap_or_default(),
+            self.todos_completed
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            self.todos_total.map(|n| n.to_string()).unwrap_or_default(),
+            self.transcript_path.clone().unwrap_or_default(),
+            self.tmux_target.clone().unwrap_or_default(),
+            self.created_at.clone(),
+            self.updated_at.clone(),
+        ]
+    }
+}
+
+impl CsvRecord for TransitionRecord {
+    const HEADER: &'static [&'static str] =
+        &["id", "session_id", "from_status", "to_status", "created_at"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.session_id.clone(),
+            self.from_status.clone().unwrap_or_default(),
+            self.to_status.clone(),
+            self.created_at.clone(),
+        ]
+    }
+}
+
+impl CsvRecord for EventRecord {
+    const HEADER: &'static [&'static str] =
+        &["id", "session_id", "event_type", "created_at", "payload"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.session_id.clone(),
+            self.event_type.clone(),
+            self.created_at.clone(),
+            self.payload.to_string(),
+        ]
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_line(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|f| csv_escape(f))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub struct ExportData {
+    pub plates: Vec<PlateRecord>,
+    pub transitions: Vec<TransitionRecord>,
+    pub events: Vec<EventRecord>,
+}
+
+pub fn collect(db: &Database, filter: &ExportFilter) -> Result<ExportData> {
+    let plates: Vec<Plate> = db
+        .get_plates()?
+        .into_iter()
+        .filter(|p| !p.session_id.starts_with("pending:"))
+        .filter(|p| filter.matches_plate(p))
+        .collect();
+    let session_ids: HashSet<&str> = plates.iter().map(|p| p.session_id.as_str()).collect();
+
+    let transitions = db
+        .get_transitions()?
+        .into_iter()
+        .filter(|t| session_ids.contains(t.session_id.as_str()))
+        .filter(|t| filter.is_after_since(&t.created_at))
+        .map(|t: Transition| TransitionRecord {
+            id: t.id,
+            session_id: t.session_id,
+            from_status: t.from_status,
+            to_status: t.to_status,
+            created_at: t.created_at,
+        })
+        .collect();
+
+    let events = db
+        .get_events()?
+        .into_iter()
+        .filter(|e| session_ids.contains(e.session_id.as_str()))
+        .filter(|e| filter.is_after_since(&e.created_at))
+        .map(|e: StoredEvent| EventRecord {
+            id: e.id,
+            session_id: e.session_id,
+            event_type: e.event_type,
+            created_at: e.created_at,
+            payload: serde_json::from_str(&e.payload).unwrap_or(serde_json::Value::Null),
+        })
+        .collect();
+
+    let mut plate_records = Vec::with_capacity(plates.len());
+    for plate in plates {
+        let goal = db.get_goal(&plate.session_id).ok().flatten();
+        let (todos_completed, todos_total) = plate.todo_counts().unzip();
+        plate_records.push(PlateRecord {
+            project_name: plate.project_name().to_string(),
+            session_id: plate.session_id,
+            project_path: plate.project_path,
+            git_branch: plate.git_branch,
+            status: plate.status.as_str().to_string(),
+            goal,
+            summary: plate.summary,
+            todos_completed,
+            todos_total,
+            transcript_path: plate.transcript_path,
+            tmux_target: plate.tmux_target,
+            created_at: plate.created_at,
+            updated_at: plate.updated_at,
+        });
+    }
+
+    Ok(ExportData {
+        plates: plate_records,
+        transitions,
+        events,
+    })
+}
+
+fn write_records<T: Serialize + CsvRecord>(
+    out: &mut dyn Write,
+    records: &[T],
+    table: ExportTable,
+    format: ExportFormat,
+) -> Result<()> {
+    match format {
+        ExportFormat::Jsonl => {
+            for record in records {
+                let mut value = serde_json::to_value(record)?;
+                if let Some(obj) = value.as_object_mut() {
+                    obj.insert("record".to_string(), table.record_name().into());
+                    obj.insert("schema_version".to_string(), EXPORT_SCHEMA_VERSION.into());
+                }
+                writeln!(out, "{}", value)?;
+            }
+        }
+        ExportFormat::Csv => {
+            let header: Vec<String> = T::HEADER.iter().map(|h| h.to_string()).collect();
+            writeln!(out, "{}", csv_line(&header))?;
+            for record in records {
+                writeln!(out, "{}", csv_line(&record.csv_fields()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_table(
+    out: &mut dyn Write,
+    data: &ExportData,
+    table: ExportTable,
+    format: ExportFormat,
+) -> Result<()> {
+    match table {
+        ExportTable::Plates => write_records(out, &data.plates, table, format),
+        ExportTable::Transitions => write_records(out, &data.transitions, table, format),
+        ExportTable::Events => write_records(out, &data.events, table, format),
+    }
+}
+
+pub fn export(
+    format: &str,
+    table: Option<&str>,
+    output: Option<&str>,
+    filter: ExportFilter,
+) -> Result<()> {
+    let format: ExportFormat = format.parse()?;
+    let tables: Vec<ExportTable> = match table {
+        Some(t) => vec![t.parse()?],
+        None => ExportTable::ALL.to_vec(),
+    };
+
+    let db = Database::open(&db_path())?;
+    let data = collect(&db, &filter)?;
+
+    match output {
+        Some(dir) => {
+            let dir = Path::new(dir);
+            std::fs::create_dir_all(dir)?;
+            for table in tables {
+                let path = dir.join(format!("{}.{}", table.name(), format.extension()));
+                let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+                write_table(&mut file, &data, table, format)?;
+                file.flush()?;
+                eprintln!("Wrote {}", path.display());
+            }
+        }
+        None => {
+            if format == ExportFormat::Csv && tables.len() > 1 {
+                bail!("CSV export to stdout needs a single --table (or use --output <dir>)");
+            }
+            let stdout = std::io::stdout();
+            let mut out = stdout.lock();
+            for table in tables {
+                write_table(&mut out, &data, table, format)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plate(session_id: &str, project_path: &str, status: PlateStatus, updated_at: &str) -> Plate {
+        Plate {
+            created_at: updated_at.to_string(),
+            updated_at: updated_at.to_string(),
+            ..Plate::for_test(session_id, project_path, status)
+        }
+    }
+
+    #[test]
+    fn csv_escapes_separators_and_quotes() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn filter_matches_project_name_or_path_and_status() {
+        let filter =
+            ExportFilter::new(Some("2026-01-02"), Some("api"), Some("idle,closed")).unwrap();
+        let p = plate(
+            "a",
+            "/work/api",
+            PlateStatus::Idle,
+            "2026-01-03T00:00:00+00:00",
+        );
+        assert!(filter.matches_plate(&p));
+
+        let running = plate(
+            "b",
+            "/work/api",
+            PlateStatus::Running,
+            "2026-01-03T00:00:00+00:00",
+        );
+        assert!(!filter.matches_plate(&running));
+
+        let old = plate(
+            "c",
+            "/work/api",
+            PlateStatus::Idle,
+            "2026-01-01T00:00:00+00:00",
+        );
+        assert!(!filter.matches_plate(&old));
+
+        let by_path = ExportFilter::new(None, Some("/work/api/"), None).unwrap();
+        assert!(by_path.matches_plate(&running));
+        assert!(!by_path.matches_plate(&plate("d", "/work/web", PlateStatus::Idle, "x")));
+    }
+
+    #[test]
+    fn unknown_status_is_rejected() {
+        assert!(ExportFilter::new(None, None, Some("sleeping")).is_err());
+    }
+
+    #[test]
+    fn todo_progress_splits_into_counts() {
+        let mut p = plate(
+            "a",
+            "/work/api",
+            PlateStatus::Idle,
+            "2026-01-01T00:00:00+00:00",
+        );
+        assert_eq!(p.todo_counts(), None);
+        p.todo_progress = Some("3/7".to_string());
+        assert_eq!(p.todo_counts(), Some((3, 7)));
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::Result;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::backup::db_path;
+use crate::daemon::summarizer;
+use crate::daemon::transcript::Transcript;
+use crate::db::Database;
+use crate::models::Todo;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportedSession {
+    pub session_id: String,
+    pub project_path: String,
+    pub git_branch: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub todos: Option<serde_json::Value>,
+    pub summary: Option<String>,
+}
+
+pub fn claude_projects_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".claude")
+        .join("projects")
+}
+
+fn normalize_timestamp(ts: &str) -> Option<String> {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc).to_rfc3339())
+}
+
+/// Reconstructs a plate from a Claude transcript. Returns None for files that
+/// have no conversation in them (or no working directory to attribute them to).
+pub fn parse_transcript(path: &Path) -> Option<ImportedSession> {
+    let file = File::open(path).ok()?;
+    let mut session = ImportedSession {
+        session_id: path.file_stem()?.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let mut has_messages = false;
+
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line.trim()) else {
+            continue;
+        };
+        let entry_type = entry.get("type").and_then(|v| v.as_str()).unwrap_or("");
+
+        if entry_type == "summary" {
+            if let Some(text) = entry.get("summary").and_then(|v| v.as_str()) {
+                session.summary = Some(text.to_string());
+            }
+            continue;
+        }
+        if !matches!(entry_type, "user" | "assistant") {
+            continue;
+        }
+        has_messages = true;
+
+        if let Some(id) = entry.get("sessionId").and_then(|v| v.as_str()) {
+            session.session_id = id.to_string();
+        }
+        if let Some(cwd) = entry.get("cwd").and_then(|v| v.as_str()) {
+            if session.project_path.is_empty() {
+                session.project_path = cwd.to_string();
+            }
+        }
+        if let Some(branch) = entry.get("gitBranch").and_then(|v| v.as_str()) {
+            if !branch.is_empty() && branch != "HEAD" {
+                session.git_branch = Some(branch.to_string());
+            }
+        }
+        if let Some(ts) = entry
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(normalize_timestamp)
+        {
+            if session.created_at.is_empty() {
+                session.created_at = ts.clone();
+            }
+            session.updated_at = ts;
+        }
+
+        if entry_type == "assistant" {
+            let blocks = entry
+                .get("message")
+                .and_then(|m| m.get("content"))
+                .and_then(|c| c.as_array());
+            for block in blocks.into_iter().flatten() {
+                if block.get("type").and_then(|v| v.as_str()) == Some("tool_use")
+                    && block.get("name").and_then(|v| v.as_str()) == Some("TodoWrite")
+                {
+                    if let Some(todos) = block.get("input").and_then(|i| i.get("todos")) {
+                        session.todos = Some(todos.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    if !has_messages || session.project_path.is_empty() {
+        return None;
+    }
+
+    if session.updated_at.is_empty() {
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        let ts = chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339();
+        session.created_at = ts.clone();
+        session.updated_at = ts;
+    }
+
+    Some(session)
+}
+
+/// Lists top-level session transcripts, skipping subagent sidechains.
+pub fn find_transcripts(root: &Path) -> Vec<PathBuf> {
+    let Ok(projects) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    let mut transcripts: Vec<PathBuf> = projects
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| std::fs::read_dir(e.path()).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter(|p| {
+            !p.file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with("agent-"))
+        })
+        .collect();
+    transcripts.sort();
+    transcripts
+}
+
+pub fn import_transcripts(dir: Option<&str>, summarize: bool) -> Result<()> {
+    let root = dir.map(PathBuf::from).unwrap_or_else(claude_projects_dir);
+    let mut summarizer = if summarize {
+        summarizer::configured()
+    } else {
+        None
+    };
+    if summarize && summarizer.is_none() {
+        eprintln!("Warning: no summarizer configured, importing without summaries");
+    }
+
+    let prices = crate::config::load_config().prices;
+    let db = Database::open(&db_path())?;
+    let mut imported = 0;
+    let mut existing = 0;
+    let mut dismissed = 0;
+    let mut skipped = 0;
+
+    for path in find_transcripts(&root) {
+        let Some(session) = parse_transcript(&path) else {
+            skipped += 1;
+            continue;
+        };
+        if db.get_status(&session.session_id)?.is_some() {
+            existing += 1;
+            continue;
+        }
+        // Deleted from the dashboard; importing it would undo that
+        if db.is_dismissed(&session.session_id)? {
+            dismissed += 1;
+            continue;
+        }
+
+        let transcript = path.to_string_lossy().to_string();
+        let mut parsed = Transcript::load(&transcript);
+        let generated = match summarizer.as_deref().zip(parsed.as_ref()) {
+            Some((s, parsed)) => match summarizer::summarize_session(s, parsed, None) {
+                Ok(result) => result,
+                Err(failure) => {
+                    eprintln!(
+                        "Warning: summarizing {} failed: {}",
+                        session.session_id, failure
+                    );
+                    // A bad key won't work for the next transcript either
+                    if failure.kind == summarizer::FailureKind::Auth {
+                        summarizer = None;
+                    }
+                    None
+                }
+            },
+            None => None,
+        };
+        let summary = generated
+            .as_ref()
+            .map(|r| r.summary.clone())
+            .or(session.summary.clone())
+            .or_else(|| {
+                let todos: Vec<Todo> = session
+                    .todos
+                    .clone()
+                    .and_then(|todos| serde_json::from_value(todos).ok())
+                    .unwrap_or_default();
+                summarizer::heuristic::summarize(parsed.as_ref()?, &todos, None).map(|r| r.summary)
+            });
+
+        let inserted = db.import_plate(
+            &session.session_id,
+            &session.project_path,
+            &transcript,
+            session.git_branch.as_deref(),
+            summary.as_deref(),
+            &session.created_at,
+            &session.updated_at,
+        )?;
+        if !inserted {
+            existing += 1;
+            continue;
+        }
+
+        if let Some(goal) = generated.and_then(|r| r.goal) {
+            db.set_goal(&session.session_id, &goal)?;
+        }
+        if let Some(todos) = &session.todos {
+            db.upsert_todos(&session.session_id, &todos.to_string(), &session.updated_at)?;
+        }
+        let prompts = summarizer::extract_user_prompts(&transcript);
+        if !prompts.is_empty() {
+            db.set_search_prompts(&session.session_id, &prompts.join("\n"))?;
+        }
+        if let Some(usage) = parsed.as_mut().and_then(Transcript::usage_update) {
+            let rows: Vec<_> = usage
+                .iter()
+                .map(|r| (r.model.as_str(), r.day.as_str(), r.totals(&prices)))
+                .collect();
+            db.set_usage(&session.session_id, &rows)?;
+        }
+
+        println!(
+            "Imported {} ({})",
+            session.session_id,
+            Path::new(&session.project_path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| session.project_path.clone())
+        );
+        imported += 1;
+    }
+
+    println!(
+        "Imported {} sessions ({} already known, {} dismissed, {} without conversation)",
+        imported, existing, dismissed, skipped
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn parses_project_branch_timestamps_and_todos() {
+        let dir = TempDir::new("import");
+        let path = dir.write(
+            "-work-api/abc.jsonl",
+            &[
+                r#"{"type":"summary","summary":"Auth migration fix"}"#,
+                r#"{"type":"user","sessionId":"abc","cwd":"/work/api","gitBranch":"fix-auth","timestamp":"2026-01-01T10:00:00.000Z","message":{"content":"fix auth"}}"#,
+                r#"{"type":"assistant","sessionId":"abc","cwd":"/work/api","timestamp":"2026-01-01T10:05:00.000Z","message":{"content":[{"type":"tool_use","name":"TodoWrite","input":{"todos":[{"content":"a","status":"completed"}]}}]}}"#,
+            ],
+        );
+
+        let session = parse_transcript(&path).unwrap();
+        assert_eq!(session.session_id, "abc");
+        assert_eq!(session.project_path, "/work/api");
+        assert_eq!(session.git_branch.as_deref(), Some("fix-auth"));
+        assert_eq!(session.created_at, "2026-01-01T10:00:00+00:00");
+        assert_eq!(session.updated_at, "2026-01-01T10:05:00+00:00");
+        assert_eq!(session.summary.as_deref(), Some("Auth migration fix"));
+        assert_eq!(session.todos.unwrap()[0]["content"], "a");
+    }
+
+    #[test]
+    fn skips_transcripts_without_conversation() {
+        let dir = TempDir::new("import");
+        let path = dir.write(
+            "-work/empty.jsonl",
+            &[r#"{"type":"summary","summary":"x"}"#],
+        );
+        assert!(parse_transcript(&path).is_none());
+    }
+
+    #[test]
+    fn finds_session_transcripts_but_not_subagents() {
+        let dir = TempDir::new("import");
+        dir.write("-work-api/one.jsonl", &[]);
+        dir.write("-work-api/agent-123.jsonl", &[]);
+        dir.write("-work-web/two.jsonl", &[]);
+        dir.write("-work-web/notes.txt", &[]);
+
+        let names: Vec<String> = find_transcripts(&dir.0)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["one.jsonl", "two.jsonl"]);
+    }
+}
@@ -1,7 +1,12 @@
 pub mod auth;
+pub mod catchup;
 pub mod config;
+pub mod db;
+pub mod export;
+pub mod import;
 pub mod install;
 pub mod kill;
 pub mod plates;
 pub mod run;
+pub mod search;
 pub mod tmux;
@@ -3,7 +3,7 @@ use anyhow::Result;
 use crate::hook::DAEMON_URL;
 
 pub fn plates() -> Result<()> {
-    let client = reqwest::blocking::Client::new();
+    let client = crate::api_token::blocking_client();
     let response = client
         .get(format!("{}/plates", DAEMON_URL))
         .timeout(std::time::Duration::from_secs(5))
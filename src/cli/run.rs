@@ -10,7 +10,7 @@ use crate::ensure_daemon_running;
 use crate::hook::DAEMON_URL;
 
 fn notify_stopped(project_path: &str) {
-    let _ = reqwest::blocking::Client::new()
+    let _ = crate::api_token::blocking_client()
         .post(format!("{}/plates/stopped", DAEMON_URL))
         .json(&serde_json::json!({"project_path": project_path}))
         .timeout(std::time::Duration::from_secs(2))
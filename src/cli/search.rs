@@ -0,0 +1,41 @@
+use anyhow::Result;
+
+use crate::hook::DAEMON_URL;
+use crate::models::Plate;
+
+pub fn search(query: &str, json: bool) -> Result<()> {
+    let client = crate::api_token::blocking_client();
+    let response = client
+        .get(format!("{}/search", DAEMON_URL))
+        .query(&[("q", query)])
+        .timeout(std::time::Duration::from_secs(5))
+        .send()?;
+
+    let plates: Vec<Plate> = response.json()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&plates)?);
+        return Ok(());
+    }
+
+    if plates.is_empty() {
+        println!("No plates match \"{}\"", query);
+        return Ok(());
+    }
+
+    for plate in &plates {
+        let label = match &plate.git_branch {
+            Some(branch) => format!("{}/{}", plate.project_name(), branch),
+            None => plate.project_name().to_string(),
+        };
+        println!(
+            "{} {}  {}  {}",
+            plate.status.icon(),
+            label,
+            plate.session_id,
+            plate.summary.as_deref().unwrap_or("")
+        );
+    }
+
+    Ok(())
+}
@@ -148,3 +148,37 @@ pub fn select_window(target: &str) -> Result<()> {
     }
     Ok(())
 }
+
+/// Types `text` into the pane at `target` and submits it. The text goes
+/// through a paste buffer so newlines arrive as part of one prompt instead
+/// of each submitting a line.
+pub fn send_prompt(target: &str, text: &str) -> Result<()> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let buffer = generate_window_name();
+    let mut child = Command::new("tmux")
+        .args(["load-buffer", "-b", &buffer, "-"])
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    if !child.wait()?.success() {
+        bail!("Failed to load prompt into tmux");
+    }
+
+    let status = Command::new("tmux")
+        .args(["paste-buffer", "-p", "-d", "-b", &buffer, "-t", target])
+        .status()?;
+    if !status.success() {
+        bail!("Failed to paste prompt into {}", target);
+    }
+    let status = Command::new("tmux")
+        .args(["send-keys", "-t", target, "Enter"])
+        .status()?;
+    if !status.success() {
+        bail!("Failed to send Enter to {}", target);
+    }
+    Ok(())
+}
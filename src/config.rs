@@ -13,6 +13,311 @@ pub struct Config {
     pub sounds: SoundsConfig,
     #[serde(default)]
     pub theme: ThemeConfig,
+    /// Browser origins allowed to call the daemon, e.g. `http://localhost:3000`.
+    /// Requests carrying any other `Origin` header are rejected.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default)]
+    pub inbox: InboxConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remotes: Vec<RemoteConfig>,
+    /// Lets daemons that list this one under `[[remotes]]` act on its
+    /// plates, e.g. dismiss them. Off by default: they only get to look.
+    #[serde(default)]
+    pub allow_remote_actions: bool,
+    #[serde(default)]
+    pub summarizer: SummarizerConfig,
+    #[serde(default, skip_serializing_if = "Prices::is_empty")]
+    pub prices: Prices,
+    #[serde(default)]
+    pub dashboard: DashboardConfig,
+}
+
+/// `[dashboard]`: how the plate list is sorted and filtered. Changed from
+/// the dashboard itself.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DashboardConfig {
+    #[serde(default)]
+    pub sort: SortMode,
+    #[serde(default)]
+    pub filter: PlateFilter,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    /// Plates needing attention first, then as the daemon lists them.
+    #[default]
+    Attention,
+    /// Most recently updated first.
+    Updated,
+    /// Newest first.
+    Created,
+    /// By project, then branch.
+    Project,
+    /// Plates waiting longest on the user first.
+    Waiting,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 5] = [
+        Self::Attention,
+        Self::Updated,
+        Self::Created,
+        Self::Project,
+        Self::Waiting,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Attention => "attention",
+            Self::Updated => "updated",
+            Self::Created => "created",
+            Self::Project => "project",
+            Self::Waiting => "waiting",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let idx = Self::ALL.iter().position(|m| m == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+/// Which plates the dashboard lists. Empty fields don't filter.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PlateFilter {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statuses: Vec<crate::models::PlateStatus>,
+    /// Project directory name or full path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Only plates with a todo list.
+    #[serde(default)]
+    pub has_todos: bool,
+}
+
+impl PlateFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn matches(&self, plate: &crate::models::Plate) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&plate.status))
+            && self.project.as_deref().is_none_or(|p| plate.in_project(p))
+            && self
+                .branch
+                .as_deref()
+                .is_none_or(|b| plate.git_branch.as_deref() == Some(b))
+            && (!self.has_todos || plate.todo_counts().is_some_and(|(_, total)| total > 0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SummarizerBackend {
+    #[default]
+    Anthropic,
+    /// Any server speaking the OpenAI chat completions API (Ollama, vLLM,
+    /// llama.cpp, gateways).
+    Openai,
+    /// Pipes the prompt to a shell command and reads the summary from stdout.
+    Command,
+}
+
+/// `[summarizer]`: which model writes plate summaries, and how to reach it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SummarizerConfig {
+    #[serde(default)]
+    pub backend: SummarizerBackend,
+    /// Defaults to a small model of the chosen backend.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// API root, e.g. `http://localhost:11434/v1` for Ollama. Defaults to
+    /// the vendor's public endpoint.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Extra headers sent with every request, e.g. `Authorization`.
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub headers: std::collections::HashMap<String, String>,
+    #[serde(default = "default_summarizer_timeout")]
+    pub timeout_secs: u64,
+    /// Shell command for the `command` backend.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Quiet period after a plate's last event before it is summarized.
+    #[serde(default = "default_debounce_secs")]
+    pub debounce_secs: u64,
+    /// Summaries generated at the same time, across all plates.
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+    #[serde(default = "default_max_per_minute")]
+    pub max_per_minute: u32,
+    /// Model calls allowed per UTC day; further jobs get the heuristic
+    /// summary. 0 means no limit.
+    #[serde(default = "default_daily_budget")]
+    pub daily_budget: u32,
+}
+
+fn default_summarizer_timeout() -> u64 {
+    30
+}
+fn default_debounce_secs() -> u64 {
+    3
+}
+fn default_max_concurrent() -> usize {
+    2
+}
+fn default_max_per_minute() -> u32 {
+    20
+}
+fn default_daily_budget() -> u32 {
+    1000
+}
+
+impl Default for SummarizerConfig {
+    fn default() -> Self {
+        Self {
+            backend: SummarizerBackend::default(),
+            model: None,
+            base_url: None,
+            headers: std::collections::HashMap::new(),
+            timeout_secs: default_summarizer_timeout(),
+            command: None,
+            debounce_secs: default_debounce_secs(),
+            max_concurrent: default_max_concurrent(),
+            max_per_minute: default_max_per_minute(),
+            daily_budget: default_daily_budget(),
+        }
+    }
+}
+
+/// A `[[remotes]]` entry: another daemon whose plates are merged into this
+/// one's, tagged with `name`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemoteConfig {
+    pub name: String,
+    /// Base URL of the remote daemon, e.g. `http://localhost:17890` for an
+    /// SSH-forwarded port.
+    pub url: String,
+    /// Contents of the remote daemon's `api-token` file.
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+pub const DEFAULT_INBOX_TEMPLATE: &str = "[{type}] {title}\n\n{body}";
+
+/// `[inbox.templates]`: dispatch prompts keyed by item type, with a
+/// `default` entry for types that have none.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct InboxConfig {
+    #[serde(default)]
+    pub templates: std::collections::HashMap<String, String>,
+}
+
+impl InboxConfig {
+    pub fn template_for(&self, item_type: &str) -> &str {
+        self.templates
+            .get(item_type)
+            .or_else(|| self.templates.get("default"))
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_INBOX_TEMPLATE)
+    }
+}
+
+/// USD per million tokens of one model.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    #[serde(default)]
+    pub cache_write: f64,
+    #[serde(default)]
+    pub cache_read: f64,
+}
+
+impl ModelPrice {
+    const fn new(input: f64, output: f64, cache_write: f64, cache_read: f64) -> Self {
+        Self {
+            input,
+            output,
+            cache_write,
+            cache_read,
+        }
+    }
+
+    pub fn cost(&self, usage: &crate::models::TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_tokens as f64 * self.cache_write
+            + usage.cache_read_tokens as f64 * self.cache_read)
+            / 1_000_000.0
+    }
+}
+
+/// Built-in list prices, used for models `[prices]` doesn't cover.
+const DEFAULT_PRICES: &[(&str, ModelPrice)] = &[
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0, 18.75, 1.5)),
+    ("claude-opus-4-5", ModelPrice::new(5.0, 25.0, 6.25, 0.5)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0, 3.75, 0.3)),
+    ("claude-haiku-4-5", ModelPrice::new(1.0, 5.0, 1.25, 0.1)),
+    ("claude-3-5-haiku", ModelPrice::new(0.8, 4.0, 1.0, 0.08)),
+];
+
+/// `[prices]`: what each model costs, keyed by model name or a prefix of
+/// one, e.g. `[prices."claude-sonnet-4"]`. The longest matching key wins;
+/// entries here override the built-in prices.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Prices(pub std::collections::HashMap<String, ModelPrice>);
+
+impl Prices {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn price_for(&self, model: &str) -> Option<ModelPrice> {
+        fn longest<'a>(
+            prices: impl Iterator<Item = (&'a str, &'a ModelPrice)>,
+            model: &str,
+        ) -> Option<ModelPrice> {
+            prices
+                .filter(|(prefix, _)| model.starts_with(prefix))
+                .max_by_key(|(prefix, _)| prefix.len())
+                .map(|(_, price)| *price)
+        }
+        longest(self.0.iter().map(|(k, v)| (k.as_str(), v)), model)
+            .or_else(|| longest(DEFAULT_PRICES.iter().map(|(k, v)| (*k, v)), model))
+    }
+
+    /// Estimated cost of `usage`; 0 for models without a price.
+    pub fn cost(&self, model: &str, usage: &crate::models::TokenUsage) -> f64 {
+        self.price_for(model)
+            .map(|price| price.cost(usage))
+            .unwrap_or(0.0)
+    }
+}
+
+/// A `[[webhooks]]` entry. Empty filters match everything.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Statuses that trigger the hook, e.g. `["awaiting_input", "error"]`.
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    /// Project directory names or full paths.
+    #[serde(default)]
+    pub projects: Vec<String>,
+    /// When set, each request carries an `X-Plate-Spinner-Signature` header
+    /// with the hex HMAC-SHA256 of the body.
+    #[serde(default)]
+    pub secret: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
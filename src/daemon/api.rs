@@ -0,0 +1,274 @@
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    routing::{get, patch, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::error::{ApiError, ApiResult};
+use super::handlers;
+use super::inbox;
+use super::sse;
+use super::state::AppState;
+use super::websocket;
+use crate::db::{parse_since, PlateQuery, PlateSort};
+use crate::models::{Plate, PlateStatus};
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// Routes served under `/api/v1`. Unlike the unversioned routes, these are
+/// kept stable: fields may be added but not renamed or removed.
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/health", get(handlers::health))
+        .route("/status", get(handlers::status))
+        .route("/shutdown", post(handlers::shutdown))
+        .route("/events", post(handlers::post_event))
+        .route("/events/stream", get(sse::event_stream))
+        .route("/plates", get(list_plates))
+        .route("/plates/register", post(handlers::register_plate))
+        .route("/plates/stopped", post(handlers::mark_stopped))
+        .route(
+            "/plates/:session_id",
+            get(handlers::get_plate).delete(handlers::delete_plate),
+        )
+        .route("/plates/:session_id/digest", get(handlers::get_digest))
+        .route("/plates/:session_id/ack", post(handlers::acknowledge))
+        .route("/usage", get(handlers::get_usage))
+        .route("/inbox", get(inbox::list_items).post(inbox::create_item))
+        .route("/inbox/:id", patch(inbox::update_item))
+        .route("/inbox/:id/dispatch", post(inbox::dispatch_item))
+        .route("/search", get(handlers::search))
+        .route("/ws", get(websocket::websocket_handler))
+        .fallback(not_found)
+}
+
+async fn not_found() -> ApiError {
+    ApiError::not_found("No such endpoint")
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListPlatesParams {
+    status: Option<String>,
+    project: Option<String>,
+    branch: Option<String>,
+    since: Option<String>,
+    needs_attention: Option<bool>,
+    sort: Option<String>,
+    order: Option<String>,
+    cursor: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlatePage {
+    pub plates: Vec<Plate>,
+    pub next_cursor: Option<String>,
+}
+
+fn encode_cursor(key: &str, session_id: &str) -> String {
+    format!("{}\n{}", key, session_id)
+        .bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn decode_cursor(cursor: &str) -> Option<(String, String)> {
+    if !cursor.len().is_multiple_of(2) {
+        return None;
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(cursor.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let text = String::from_utf8(bytes).ok()?;
+    let (key, session_id) = text.split_once('\n')?;
+    Some((key.to_string(), session_id.to_string()))
+}
+
+fn parse_statuses(list: &str) -> Result<Vec<PlateStatus>, ApiError> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| ApiError::bad_request(format!("Unknown status: {}", s)))
+        })
+        .collect()
+}
+
+fn build_query(params: ListPlatesParams) -> Result<PlateQuery, ApiError> {
+    let mut statuses = match params.status.as_deref() {
+        Some(list) => parse_statuses(list)?,
+        None => Vec::new(),
+    };
+    if let Some(needs_attention) = params.needs_attention {
+        let all = [
+            PlateStatus::Starting,
+            PlateStatus::Running,
+            PlateStatus::Idle,
+            PlateStatus::AwaitingInput,
+            PlateStatus::AwaitingApproval,
+            PlateStatus::Error,
+            PlateStatus::Closed,
+        ];
+        let base = if statuses.is_empty() {
+            all.to_vec()
+        } else {
+            statuses
+        };
+        statuses = base
+            .into_iter()
+            .filter(|s| s.needs_attention() == needs_attention)
+            .collect();
+        if statuses.is_empty() {
+            return Err(ApiError::bad_request(
+                "status and needs_attention filters exclude every plate",
+            ));
+        }
+    }
+
+    let sort = match params.sort.as_deref() {
+        Some(s) => s.parse().map_err(|_| {
+            ApiError::bad_request(format!(
+                "Unknown sort: {} (use updated_at, created_at, project or status)",
+                s
+            ))
+        })?,
+        None => PlateSort::default(),
+    };
+    let ascending = match params.order.as_deref() {
+        Some("asc") => true,
+        Some("desc") | None => false,
+        Some(other) => {
+            return Err(ApiError::bad_request(format!(
+                "Unknown order: {} (use asc or desc)",
+                other
+            )))
+        }
+    };
+
+    let since = params
+        .since
+        .as_deref()
+        .map(parse_since)
+        .transpose()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let after = params
+        .cursor
+        .as_deref()
+        .map(|c| decode_cursor(c).ok_or_else(|| ApiError::bad_request("Invalid cursor")))
+        .transpose()?;
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ApiError::bad_request(format!(
+            "limit must be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+
+    Ok(PlateQuery {
+        statuses,
+        project: params.project,
+        branch: params.branch,
+        since,
+        sort,
+        ascending,
+        after,
+        limit,
+    })
+}
+
+pub async fn list_plates(
+    State(state): State<Arc<AppState>>,
+    params: Result<Query<ListPlatesParams>, QueryRejection>,
+) -> ApiResult<PlatePage> {
+    let Query(params) = params.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let mut query = build_query(params)?;
+    let limit = query.limit;
+    // Fetch one extra row to learn whether another page exists
+    query.limit += 1;
+
+    let mut plates = {
+        let db = state.db.lock().unwrap();
+        db.query_plates(&query)?
+    };
+
+    let next_cursor = if plates.len() > limit {
+        plates.truncate(limit);
+        plates
+            .last()
+            .map(|p| encode_cursor(&query.sort.key(p), &p.session_id))
+    } else {
+        None
+    };
+
+    Ok(Json(PlatePage {
+        plates,
+        next_cursor,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = encode_cursor("2026-01-01T00:00:00+00:00", "abc-123");
+        assert_eq!(
+            decode_cursor(&cursor),
+            Some((
+                "2026-01-01T00:00:00+00:00".to_string(),
+                "abc-123".to_string()
+            ))
+        );
+        assert_eq!(decode_cursor("zz"), None);
+        assert_eq!(decode_cursor("abc"), None);
+    }
+
+    #[test]
+    fn needs_attention_narrows_statuses() {
+        let query = build_query(ListPlatesParams {
+            status: Some("running,idle".to_string()),
+            needs_attention: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(query.statuses, vec![PlateStatus::Idle]);
+
+        let err = build_query(ListPlatesParams {
+            status: Some("running".to_string()),
+            needs_attention: Some(true),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(err.code, "invalid_parameter");
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        for params in [
+            ListPlatesParams {
+                status: Some("sleeping".to_string()),
+                ..Default::default()
+            },
+            ListPlatesParams {
+                sort: Some("size".to_string()),
+                ..Default::default()
+            },
+            ListPlatesParams {
+                limit: Some(0),
+                ..Default::default()
+            },
+            ListPlatesParams {
+                cursor: Some("nothex".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(build_query(params).is_err());
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use super::error::ApiError;
+
+/// Paths that answer without a token, so clients can tell whether a daemon
+/// is running (and which version) before it has written the token file.
+const PUBLIC_PATHS: &[&str] = &["/health", "/api/v1/health"];
+
+pub struct ApiAuth {
+    pub token: String,
+    pub allowed_origins: Vec<String>,
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl ApiAuth {
+    /// Rejects browser requests from origins that aren't allowlisted, then
+    /// requests without the bearer token.
+    pub fn check(&self, path: &str, headers: &HeaderMap) -> Result<(), ApiError> {
+        if let Some(origin) = headers.get(header::ORIGIN) {
+            let origin = origin.to_str().unwrap_or_default().trim_end_matches('/');
+            if !self
+                .allowed_origins
+                .iter()
+                .any(|allowed| allowed.trim_end_matches('/') == origin)
+            {
+                return Err(ApiError::new(
+                    StatusCode::FORBIDDEN,
+                    "forbidden_origin",
+                    format!("Origin {} is not allowed", origin),
+                ));
+            }
+        }
+
+        if PUBLIC_PATHS.contains(&path) {
+            return Ok(());
+        }
+
+        let presented = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim);
+        match presented {
+            Some(token) if constant_time_eq(token.as_bytes(), self.token.as_bytes()) => Ok(()),
+            _ => Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "unauthorized",
+                "Missing or invalid API token",
+            )),
+        }
+    }
+}
+
+pub async fn require_token(
+    State(auth): State<Arc<ApiAuth>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match auth.check(request.uri().path(), request.headers()) {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn auth() -> ApiAuth {
+        ApiAuth {
+            token: "secret".to_string(),
+            allowed_origins: vec!["http://localhost:3000".to_string()],
+        }
+    }
+
+    fn headers(pairs: &[(header::HeaderName, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn requires_matching_token() {
+        let auth = auth();
+        assert_eq!(
+            auth.check("/plates", &HeaderMap::new()).unwrap_err().code,
+            "unauthorized"
+        );
+        assert!(auth
+            .check(
+                "/plates",
+                &headers(&[(header::AUTHORIZATION, "Bearer wrong")])
+            )
+            .is_err());
+        assert!(auth
+            .check(
+                "/plates",
+                &headers(&[(header::AUTHORIZATION, "Bearer secret")])
+            )
+            .is_ok());
+        assert!(auth.check("/health", &HeaderMap::new()).is_ok());
+    }
+
+    #[test]
+    fn rejects_unlisted_origins_even_with_token() {
+        let auth = auth();
+        let err = auth
+            .check(
+                "/health",
+                &headers(&[(header::ORIGIN, "https://evil.example")]),
+            )
+            .unwrap_err();
+        assert_eq!(err.code, "forbidden_origin");
+        assert!(auth
+            .check(
+                "/plates",
+                &headers(&[
+                    (header::ORIGIN, "http://localhost:3000"),
+                    (header::AUTHORIZATION, "Bearer secret"),
+                ])
+            )
+            .is_ok());
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::backup::{
+    backup_dir, create_backup, db_path, next_backup_in, open_backup_source, StartupCheck,
+    BACKUPS_TO_KEEP, BACKUP_INTERVAL_SECS,
+};
+
+use super::state::AppState;
+
+pub fn spawn_backup_task(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(BACKUP_INTERVAL_SECS);
+        // A database that started out empty gets a full interval before its
+        // first backup, so restarts can't rotate the good backups away
+        let first = if state.db_health.lock().unwrap().startup == StartupCheck::StartedFresh {
+            interval
+        } else {
+            next_backup_in(&backup_dir(), interval)
+        };
+        tokio::time::sleep(first).await;
+        loop {
+            let task_state = state.clone();
+            let _ = tokio::task::spawn_blocking(move || run_backup(&task_state)).await;
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+fn run_backup(state: &AppState) {
+    // Backs up from a connection of its own, leaving `state.db` to handlers
+    let result = open_backup_source(&db_path())
+        .and_then(|source| create_backup(&source, &backup_dir(), BACKUPS_TO_KEEP));
+
+    let mut health = state.db_health.lock().unwrap();
+    match result {
+        Ok(path) => {
+            health.last_backup = Some(path.display().to_string());
+            health.last_backup_at = Some(chrono::Utc::now().to_rfc3339());
+            health.last_backup_error = None;
+        }
+        Err(e) => {
+            health.last_backup_error = Some(e.to_string());
+        }
+    }
+}
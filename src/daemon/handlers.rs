@@ -1,13 +1,23 @@
 use axum::{
-    extract::{Path, State},
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        Path, Query, State,
+    },
+    http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use super::error::{ApiError, ApiResult};
+use super::metrics::METRICS;
+use super::remotes::{self, RemoteHealth};
 use super::state::{AppState, WsMessage};
-use super::summarizer;
-use crate::models::{HookEvent, PlateStatus};
+use super::summarizer::health::SummarizerHealth;
+use super::summarizer::{self, queue::QueueStats, queue::SummaryJob};
+use crate::backup::DbHealth;
+use crate::db::{parse_since, WebhookHealth};
+use crate::models::{Digest, HookEvent, PlateDetail, PlateStatus, UsageReport};
 use crate::state_machine::Event;
 
 #[derive(Serialize)]
@@ -17,6 +27,16 @@ pub struct StatusResponse {
     api_key_configured: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     hooks_installed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    database: Option<DbHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    webhooks: Option<WebhookHealth>,
+    /// Whether daemons aggregating this one may act on its plates.
+    remote_actions: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    remotes: Vec<RemoteHealth>,
+    summarizer_queue: QueueStats,
+    summarizer: SummarizerHealth,
 }
 
 pub async fn health() -> Json<serde_json::Value> {
@@ -34,12 +54,20 @@ pub async fn shutdown() -> Json<serde_json::Value> {
     Json(serde_json::json!({"status": "ok"}))
 }
 
-pub async fn status() -> Json<StatusResponse> {
+pub async fn status(State(state): State<Arc<AppState>>) -> Json<StatusResponse> {
     let api_key_configured = summarizer::get_api_key().is_some();
+    let database = state.db_health.lock().unwrap().clone();
+    let webhooks = state.db.lock().unwrap().webhook_health().ok();
     Json(StatusResponse {
         status: "ok".to_string(),
         api_key_configured: Some(api_key_configured),
         hooks_installed: Some(true),
+        database: Some(database),
+        webhooks,
+        remote_actions: state.allows_remote_actions(),
+        remotes: state.remotes.lock().unwrap().health(),
+        summarizer_queue: state.summaries.stats(),
+        summarizer: state.summarizer_failures.health(),
     })
 }
 
@@ -75,52 +103,78 @@ fn maybe_summarize(state: Arc<AppState>, event: HookEvent, status: PlateStatus)
         return;
     }
 
-    let transcript_path = event.transcript_path.clone().or_else(|| {
+    let Some(transcript) = transcript_path(&state, &event) else {
+        return;
+    };
+
+    state.summaries.enqueue(SummaryJob {
+        session_id: event.session_id,
+        transcript_path: transcript,
+    });
+}
+
+/// The event's transcript, or the one recorded for its plate.
+fn transcript_path(state: &AppState, event: &HookEvent) -> Option<String> {
+    event.transcript_path.clone().or_else(|| {
         let db = state.db.lock().unwrap();
         db.get_transcript_path(&event.session_id).ok().flatten()
-    });
+    })
+}
 
-    let Some(transcript) = transcript_path else {
+fn maybe_index_prompts(state: Arc<AppState>, event: &HookEvent) {
+    if !matches!(event.event_type.as_str(), "session_start" | "stop") {
+        return;
+    }
+
+    let Some(transcript) = transcript_path(&state, event) else {
         return;
     };
 
     let session_id = event.session_id.clone();
-    let tx = state.tx.clone();
+    tokio::task::spawn_blocking(move || {
+        let prompts = summarizer::extract_user_prompts(&transcript);
+        if !prompts.is_empty() {
+            let db = state.db.lock().unwrap();
+            let _ = db.set_search_prompts(&session_id, &prompts.join("\n"));
+        }
+    });
+}
 
-    let cached_goal = {
-        let db = state.db.lock().unwrap();
-        db.get_goal(&session_id).ok().flatten().or_else(|| {
-            // Fall back to extracting goal from existing summary if it has colon format
-            db.get_summary(&session_id)
-                .ok()
-                .flatten()
-                .and_then(|s| s.split_once(':').map(|(g, _)| g.trim().to_string()))
-        })
+/// Catches up on the plate's transcript and, if its usage changed, replaces
+/// the plate's token usage and costs with the transcript's totals.
+fn record_usage(state: Arc<AppState>, event: &HookEvent) {
+    let Some(transcript) = transcript_path(&state, event) else {
+        return;
     };
 
+    let session_id = event.session_id.clone();
     tokio::task::spawn_blocking(move || {
-        if let Some(result) = summarizer::summarize_session(&transcript, cached_goal.as_deref()) {
-            let db = state.db.lock().unwrap();
-            if let Some(goal) = result.goal {
-                let _ = db.set_goal(&session_id, &goal);
-            }
-            if db.set_summary(&session_id, &result.summary).is_ok() {
-                let _ = tx.send(WsMessage::PlateUpdate(session_id));
-            }
-        }
+        let Some(usage) = state.transcripts.usage_update(&session_id, &transcript) else {
+            return;
+        };
+        let prices = state.prices.lock().unwrap().clone();
+        let rows: Vec<_> = usage
+            .iter()
+            .map(|r| (r.model.as_str(), r.day.as_str(), r.totals(&prices)))
+            .collect();
+        let _ = state.db.lock().unwrap().set_usage(&session_id, &rows);
+        let _ = state.tx.send(WsMessage::PlateUpdate(session_id));
     });
 }
 
 pub async fn post_event(
     State(state): State<Arc<AppState>>,
-    Json(event): Json<HookEvent>,
-) -> Json<serde_json::Value> {
+    event: Result<Json<HookEvent>, JsonRejection>,
+) -> ApiResult<serde_json::Value> {
+    let Json(event) = event.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let started = std::time::Instant::now();
     let now = chrono::Utc::now().to_rfc3339();
     let status = determine_status(&event);
 
     {
         let db = state.db.lock().unwrap();
-        let _ = db.upsert_plate(
+        let previous = db.get_status(&event.session_id)?;
+        db.upsert_plate(
             &event.session_id,
             &event.project_path,
             event.transcript_path.as_deref(),
@@ -130,35 +184,80 @@ pub async fn post_event(
             &event.event_type,
             event.tool_name.as_deref(),
             &now,
-        );
+        )?;
+        if previous.as_deref() != Some(status.as_str()) {
+            METRICS.record_transition(status);
+        }
+
+        if status == PlateStatus::AwaitingInput {
+            if let Some(questions) = event
+                .tool_params
+                .as_ref()
+                .and_then(|params| params.get("questions"))
+            {
+                db.set_pending_questions(&event.session_id, &questions.to_string())?;
+            }
+        }
+
+        if status == PlateStatus::AwaitingApproval {
+            if let Some(plan) = event
+                .tool_params
+                .as_ref()
+                .and_then(|params| params.get("plan"))
+                .and_then(|plan| plan.as_str())
+            {
+                db.set_plan(&event.session_id, plan)?;
+            }
+        }
 
         if event.tool_name.as_deref() == Some("TodoWrite") {
             if let Some(params) = &event.tool_params {
                 if let Some(todos) = params.get("todos") {
-                    let _ = db.upsert_todos(&event.session_id, &todos.to_string(), &now);
+                    db.upsert_todos(&event.session_id, &todos.to_string(), &now)?;
                 }
             }
         }
 
-        let _ = db.insert_event(
+        db.insert_event(
             &event.session_id,
             &event.event_type,
             &serde_json::to_string(&event).unwrap_or_default(),
             &now,
-        );
+        )?;
     }
 
     maybe_summarize(state.clone(), event.clone(), status);
+    maybe_index_prompts(state.clone(), &event);
+    record_usage(state.clone(), &event);
 
     let _ = state
         .tx
         .send(WsMessage::PlateUpdate(event.session_id.clone()));
-    Json(serde_json::json!({"status": "ok"}))
+    METRICS.hook_ingest.observe(started.elapsed());
+    Ok(Json(serde_json::json!({"status": "ok"})))
+}
+
+pub async fn get_plates(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Vec<crate::models::Plate>> {
+    Ok(Json(state.all_plates()?))
 }
 
-pub async fn get_plates(State(state): State<Arc<AppState>>) -> Json<Vec<crate::models::Plate>> {
-    let db = state.db.lock().unwrap();
-    Json(db.get_plates().unwrap_or_default())
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    q: String,
+}
+
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SearchQuery>,
+) -> ApiResult<Vec<crate::models::Plate>> {
+    let mut plates = state.db.lock().unwrap().search_plates(&query.q)?;
+    if !query.q.trim().is_empty() {
+        plates.extend(state.remotes.lock().unwrap().search(&query.q));
+    }
+    Ok(Json(plates))
 }
 
 #[derive(Deserialize)]
@@ -168,43 +267,178 @@ pub struct RegisterRequest {
 
 pub async fn register_plate(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<RegisterRequest>,
-) -> Json<serde_json::Value> {
+    req: Result<Json<RegisterRequest>, JsonRejection>,
+) -> ApiResult<serde_json::Value> {
+    let Json(req) = req.map_err(|e| ApiError::bad_request(e.body_text()))?;
     let now = chrono::Utc::now().to_rfc3339();
     let placeholder_id = {
         let db = state.db.lock().unwrap();
-        db.register_placeholder(&req.project_path, &now)
-            .unwrap_or_default()
+        db.register_placeholder(&req.project_path, &now)?
     };
     let _ = state
         .tx
         .send(WsMessage::PlateUpdate(placeholder_id.clone()));
-    Json(serde_json::json!({"status": "ok", "placeholder_id": placeholder_id}))
+    Ok(Json(
+        serde_json::json!({"status": "ok", "placeholder_id": placeholder_id}),
+    ))
 }
 
 pub async fn mark_stopped(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<RegisterRequest>,
-) -> Json<serde_json::Value> {
+    req: Result<Json<RegisterRequest>, JsonRejection>,
+) -> ApiResult<serde_json::Value> {
+    let Json(req) = req.map_err(|e| ApiError::bad_request(e.body_text()))?;
     let now = chrono::Utc::now().to_rfc3339();
     let plate_ids = {
         let db = state.db.lock().unwrap();
-        db.mark_stopped(&req.project_path, &now).unwrap_or_default()
+        db.mark_stopped(&req.project_path, &now)?
     };
     for plate_id in &plate_ids {
+        METRICS.record_transition(PlateStatus::Closed);
         let _ = state.tx.send(WsMessage::PlateUpdate(plate_id.clone()));
     }
-    Json(serde_json::json!({"status": "ok", "count": plate_ids.len()}))
+    Ok(Json(
+        serde_json::json!({"status": "ok", "count": plate_ids.len()}),
+    ))
+}
+
+pub async fn get_plate(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> ApiResult<PlateDetail> {
+    let detail = state.db.lock().unwrap().get_plate_detail(&session_id)?;
+    if let Some(detail) = detail {
+        return Ok(Json(detail));
+    }
+
+    let remote = state.remotes.lock().unwrap().find(&session_id);
+    let Some((config, plate)) = remote else {
+        return Err(ApiError::not_found(format!("No plate {}", session_id)));
+    };
+    let path = format!("/plates/{}", session_id);
+    let mut detail: PlateDetail = remotes::remote_client(&config)
+        .get(remotes::remote_url(&config, &path))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(remote_error)?
+        .json()
+        .await
+        .map_err(remote_error)?;
+    detail.plate.host = plate.host;
+    detail.plate.read_only = plate.read_only;
+    Ok(Json(detail))
+}
+
+#[derive(Deserialize)]
+pub struct DigestParams {
+    since: Option<String>,
+}
+
+/// What a local plate did since it was last acknowledged, or since `since`.
+pub async fn get_digest(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    params: Result<Query<DigestParams>, QueryRejection>,
+) -> ApiResult<Digest> {
+    let Query(params) = params.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let since = params
+        .since
+        .as_deref()
+        .map(parse_since)
+        .transpose()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let digest = state
+        .db
+        .lock()
+        .unwrap()
+        .get_digest(&session_id, since.as_deref())?;
+    digest
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("No plate {}", session_id)))
+}
+
+#[derive(Deserialize)]
+pub struct UsageParams {
+    since: Option<String>,
+}
+
+/// Token usage and cost per project and per UTC day.
+pub async fn get_usage(
+    State(state): State<Arc<AppState>>,
+    params: Result<Query<UsageParams>, QueryRejection>,
+) -> ApiResult<UsageReport> {
+    let Query(params) = params.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let since = params
+        .since
+        .as_deref()
+        .map(|since| {
+            chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d")
+                .map(|day| day.to_string())
+                .map_err(|_| {
+                    ApiError::bad_request(format!("Invalid date: {} (use YYYY-MM-DD)", since))
+                })
+        })
+        .transpose()?;
+    Ok(Json(
+        state.db.lock().unwrap().usage_report(since.as_deref())?,
+    ))
+}
+
+pub async fn acknowledge(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> ApiResult<serde_json::Value> {
+    let now = chrono::Utc::now().to_rfc3339();
+    if !state.db.lock().unwrap().acknowledge(&session_id, &now)? {
+        return Err(ApiError::not_found(format!("No plate {}", session_id)));
+    }
+    Ok(Json(serde_json::json!({"acknowledged_at": now})))
+}
+
+fn remote_error(e: reqwest::Error) -> ApiError {
+    ApiError::new(
+        StatusCode::BAD_GATEWAY,
+        "remote_error",
+        format!("Remote daemon request failed: {}", e),
+    )
 }
 
 pub async fn delete_plate(
     State(state): State<Arc<AppState>>,
     Path(session_id): Path<String>,
-) -> Json<serde_json::Value> {
-    {
+) -> ApiResult<serde_json::Value> {
+    let deleted = {
         let db = state.db.lock().unwrap();
-        let _ = db.delete_plate(&session_id);
+        db.delete_plate(&session_id)?
+    };
+    if deleted {
+        let _ = state.tx.send(WsMessage::PlateDeleted(session_id));
+        return Ok(Json(serde_json::json!({"status": "ok"})));
     }
-    let _ = state.tx.send(WsMessage::PlateDeleted(session_id));
-    Json(serde_json::json!({"status": "ok"}))
+
+    let remote = state.remotes.lock().unwrap().find(&session_id);
+    let Some((config, plate)) = remote else {
+        return Err(ApiError::not_found(format!("No plate {}", session_id)));
+    };
+    if plate.read_only {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "read_only",
+            format!(
+                "Plate {} belongs to {}, which doesn't allow remote actions",
+                session_id, config.name
+            ),
+        ));
+    }
+    // The remote announces the deletion over its WebSocket, which removes
+    // the plate here as well.
+    let path = format!("/plates/{}", session_id);
+    remotes::remote_client(&config)
+        .delete(remotes::remote_url(&config, &path))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(remote_error)?;
+    Ok(Json(serde_json::json!({"status": "ok"})))
 }
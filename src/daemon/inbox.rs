@@ -0,0 +1,276 @@
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::error::{ApiError, ApiResult};
+use super::state::{AppState, WsMessage};
+use crate::models::{InboxItem, InboxStatus};
+
+#[derive(Debug, Deserialize)]
+pub struct NewInboxItem {
+    #[serde(rename = "type")]
+    item_type: String,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    context: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListInboxParams {
+    status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InboxUpdate {
+    status: InboxStatus,
+    #[serde(default)]
+    snoozed_until: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DispatchRequest {
+    session_id: String,
+    /// Sent instead of the rendered template when given.
+    #[serde(default)]
+    prompt: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Dispatched {
+    item: InboxItem,
+    prompt: String,
+}
+
+/// Fills `{type}`, `{title}`, `{body}` and `{context.KEY}` placeholders.
+/// Missing values render as empty strings; unknown placeholders are kept.
+pub fn render_template(template: &str, item: &InboxItem) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let key = &after[..end];
+        match placeholder(key, item) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn placeholder(key: &str, item: &InboxItem) -> Option<String> {
+    match key {
+        "type" => Some(item.item_type.clone()),
+        "title" => Some(item.title.clone()),
+        "body" => Some(item.body.clone().unwrap_or_default()),
+        _ => {
+            let field = key.strip_prefix("context.")?;
+            let value = item.context.as_ref().and_then(|c| c.get(field));
+            Some(match value {
+                None | Some(serde_json::Value::Null) => String::new(),
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+            })
+        }
+    }
+}
+
+fn parse_statuses(param: Option<&str>) -> Result<Vec<InboxStatus>, ApiError> {
+    param
+        .into_iter()
+        .flat_map(|s| s.split(','))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| ApiError::bad_request(format!("Unknown inbox status: {}", s)))
+        })
+        .collect()
+}
+
+fn parse_body<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|e| ApiError::bad_request(e.to_string()))
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Takes the raw body rather than `Json` so `curl -d` without a
+/// content-type header works.
+pub async fn create_item(
+    State(state): State<Arc<AppState>>,
+    body: String,
+) -> Result<(StatusCode, Json<InboxItem>), ApiError> {
+    let new: NewInboxItem = parse_body(&body)?;
+    if new.item_type.trim().is_empty() || new.title.trim().is_empty() {
+        return Err(ApiError::bad_request("type and title are required"));
+    }
+    let item = state.db.lock().unwrap().insert_inbox_item(
+        new.item_type.trim(),
+        new.title.trim(),
+        new.body.as_deref(),
+        new.context.as_ref(),
+        &now(),
+    )?;
+    let _ = state.tx.send(WsMessage::InboxUpdate);
+    Ok((StatusCode::CREATED, Json(item)))
+}
+
+pub async fn list_items(
+    State(state): State<Arc<AppState>>,
+    params: Result<Query<ListInboxParams>, QueryRejection>,
+) -> ApiResult<Vec<InboxItem>> {
+    let Query(params) = params.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let statuses = parse_statuses(params.status.as_deref())?;
+    let items = state.db.lock().unwrap().list_inbox(&statuses, &now())?;
+    Ok(Json(items))
+}
+
+pub async fn update_item(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    body: String,
+) -> ApiResult<InboxItem> {
+    let update: InboxUpdate = parse_body(&body)?;
+    let snoozed_until = match update.status {
+        InboxStatus::Snoozed => {
+            let until = update
+                .snoozed_until
+                .as_deref()
+                .ok_or_else(|| ApiError::bad_request("snoozed_until is required to snooze"))?;
+            let until = chrono::DateTime::parse_from_rfc3339(until)
+                .map_err(|_| ApiError::bad_request("snoozed_until must be an RFC 3339 time"))?;
+            Some(until.with_timezone(&chrono::Utc).to_rfc3339())
+        }
+        _ => None,
+    };
+
+    let item = {
+        let db = state.db.lock().unwrap();
+        if !db.update_inbox_item(id, update.status, snoozed_until.as_deref(), None, &now())? {
+            return Err(ApiError::not_found(format!("No inbox item {}", id)));
+        }
+        db.get_inbox_item(id)?
+    };
+    let _ = state.tx.send(WsMessage::InboxUpdate);
+    item.map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("No inbox item {}", id)))
+}
+
+pub async fn dispatch_item(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    body: String,
+) -> ApiResult<Dispatched> {
+    let request: DispatchRequest = parse_body(&body)?;
+    let item = state.db.lock().unwrap().get_inbox_item(id)?;
+    let plate = state.find_plate(&request.session_id)?;
+    let item = item.ok_or_else(|| ApiError::not_found(format!("No inbox item {}", id)))?;
+    let plate =
+        plate.ok_or_else(|| ApiError::not_found(format!("No plate {}", request.session_id)))?;
+    if let Some(host) = plate.host {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "remote_plate",
+            format!("Plate {} runs on {}", request.session_id, host),
+        ));
+    }
+    let Some(target) = plate.tmux_target else {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "no_tmux_target",
+            format!("Plate {} is not running in tmux", request.session_id),
+        ));
+    };
+
+    let prompt = match request.prompt {
+        Some(prompt) => prompt,
+        None => {
+            let config = crate::config::load_config();
+            render_template(config.inbox.template_for(&item.item_type), &item)
+        }
+    };
+    let text = prompt.clone();
+    tokio::task::spawn_blocking(move || crate::cli::tmux::send_prompt(&target, &text))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))??;
+
+    let item = {
+        let db = state.db.lock().unwrap();
+        db.update_inbox_item(
+            id,
+            InboxStatus::Dispatched,
+            None,
+            Some(&request.session_id),
+            &now(),
+        )?;
+        db.get_inbox_item(id)?
+    };
+    let _ = state.tx.send(WsMessage::InboxUpdate);
+    let item = item.ok_or_else(|| ApiError::not_found(format!("No inbox item {}", id)))?;
+    Ok(Json(Dispatched { item, prompt }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(context: Option<serde_json::Value>) -> InboxItem {
+        InboxItem {
+            id: 1,
+            item_type: "review".to_string(),
+            title: "Changes requested on #42".to_string(),
+            body: None,
+            context,
+            status: InboxStatus::Pending,
+            snoozed_until: None,
+            dispatched_to: None,
+            created_at: "2026-01-01T00:00:00+00:00".to_string(),
+            updated_at: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn renders_fields_and_context() {
+        let item = item(Some(serde_json::json!({"url": "https://x/42", "pr": 42})));
+        assert_eq!(
+            render_template(
+                "[{type}] {title}\n{body}\nPR {context.pr}: {context.url}",
+                &item
+            ),
+            "[review] Changes requested on #42\n\nPR 42: https://x/42"
+        );
+    }
+
+    #[test]
+    fn missing_context_is_empty_and_unknown_placeholders_stay() {
+        let item = item(None);
+        assert_eq!(
+            render_template("{context.url}|{other}|{unclosed", &item),
+            "|{other}|{unclosed"
+        );
+    }
+
+    #[test]
+    fn parses_status_lists() {
+        assert_eq!(parse_statuses(None).unwrap(), vec![]);
+        assert_eq!(
+            parse_statuses(Some("pending, snoozed")).unwrap(),
+            vec![InboxStatus::Pending, InboxStatus::Snoozed]
+        );
+        assert!(parse_statuses(Some("done")).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use axum::{extract::State, http::header, response::IntoResponse};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::error::ApiError;
+use super::state::AppState;
+use crate::models::PlateStatus;
+
+const BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+const ALL_STATUSES: [PlateStatus; 7] = [
+    PlateStatus::Starting,
+    PlateStatus::Running,
+    PlateStatus::Idle,
+    PlateStatus::AwaitingInput,
+    PlateStatus::AwaitingApproval,
+    PlateStatus::Error,
+    PlateStatus::Closed,
+];
+
+/// A latency histogram with fixed buckets, in the Prometheus sense:
+/// each bucket counts observations less than or equal to its bound.
+pub struct Histogram {
+    buckets: [AtomicU64; BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; BUCKETS.len()],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        for (bound, bucket) in BUCKETS.iter().zip(&self.buckets) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (bound, bucket) in BUCKETS.iter().zip(&self.buckets) {
+            let _ = writeln!(
+                out,
+                "{}_bucket{{le=\"{}\"}} {}",
+                name,
+                bound,
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+        let _ = writeln!(
+            out,
+            "{}_sum {}",
+            name,
+            self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        );
+        let _ = writeln!(out, "{}_count {}", name, count);
+    }
+}
+
+/// Process-wide counters. A static rather than part of `AppState` so the
+/// summarizer, which has no handle on the state, can record into it too.
+pub struct Metrics {
+    pub hook_ingest: Histogram,
+    pub summarizer_calls: AtomicU64,
+    pub summarizer_failures: AtomicU64,
+    pub summarizer_latency: Histogram,
+    pub health_check: Histogram,
+    pub websocket_clients: AtomicI64,
+    /// Status transitions since the daemon started, indexed like
+    /// `ALL_STATUSES`.
+    transitions: [AtomicU64; ALL_STATUSES.len()],
+}
+
+pub static METRICS: Metrics = Metrics::new();
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            hook_ingest: Histogram::new(),
+            summarizer_calls: AtomicU64::new(0),
+            summarizer_failures: AtomicU64::new(0),
+            summarizer_latency: Histogram::new(),
+            health_check: Histogram::new(),
+            websocket_clients: AtomicI64::new(0),
+            transitions: [const { AtomicU64::new(0) }; ALL_STATUSES.len()],
+        }
+    }
+
+    pub fn record_transition(&self, to_status: PlateStatus) {
+        if let Some(idx) = ALL_STATUSES.iter().position(|s| *s == to_status) {
+            self.transitions[idx].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_summarizer_call(&self, elapsed: Duration, ok: bool) {
+        self.summarizer_calls.fetch_add(1, Ordering::Relaxed);
+        if !ok {
+            self.summarizer_failures.fetch_add(1, Ordering::Relaxed);
+        }
+        self.summarizer_latency.observe(elapsed);
+    }
+}
+
+/// Counts a connected WebSocket client for as long as it is alive.
+pub struct ClientGuard;
+
+impl ClientGuard {
+    pub fn connect() -> Self {
+        METRICS.websocket_clients.fetch_add(1, Ordering::Relaxed);
+        Self
+    }
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        METRICS.websocket_clients.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Values read from the database at scrape time.
+#[derive(Debug, Default)]
+pub struct DbStats {
+    pub plates_by_status: Vec<(String, i64)>,
+    pub size_bytes: i64,
+}
+
+fn metric_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn count_for(counts: &[(String, i64)], status: PlateStatus) -> i64 {
+    counts
+        .iter()
+        .find(|(s, _)| s == status.as_str())
+        .map(|(_, n)| *n)
+        .unwrap_or(0)
+}
+
+pub fn render(metrics: &Metrics, stats: &DbStats) -> String {
+    let mut out = String::new();
+
+    metric_header(
+        &mut out,
+        "plate_spinner_plates",
+        "gauge",
+        "Plates by status",
+    );
+    for status in ALL_STATUSES {
+        let _ = writeln!(
+            out,
+            "plate_spinner_plates{{status=\"{}\"}} {}",
+            status.as_str(),
+            count_for(&stats.plates_by_status, status)
+        );
+    }
+
+    metric_header(
+        &mut out,
+        "plate_spinner_transitions_total",
+        "counter",
+        "Status transitions recorded, by new status",
+    );
+    for (status, count) in ALL_STATUSES.iter().zip(&metrics.transitions) {
+        let _ = writeln!(
+            out,
+            "plate_spinner_transitions_total{{to_status=\"{}\"}} {}",
+            status.as_str(),
+            count.load(Ordering::Relaxed)
+        );
+    }
+
+    metrics.hook_ingest.render(
+        &mut out,
+        "plate_spinner_hook_ingest_seconds",
+        "Time to ingest a hook event",
+    );
+
+    metric_header(
+        &mut out,
+        "plate_spinner_summarizer_calls_total",
+        "counter",
+        "Summarizer API calls",
+    );
+    let _ = writeln!(
+        out,
+        "plate_spinner_summarizer_calls_total {}",
+        metrics.summarizer_calls.load(Ordering::Relaxed)
+    );
+    metric_header(
+        &mut out,
+        "plate_spinner_summarizer_failures_total",
+        "counter",
+        "Summarizer API calls that failed",
+    );
+    let _ = writeln!(
+        out,
+        "plate_spinner_summarizer_failures_total {}",
+        metrics.summarizer_failures.load(Ordering::Relaxed)
+    );
+    metrics.summarizer_latency.render(
+        &mut out,
+        "plate_spinner_summarizer_seconds",
+        "Summarizer API call latency",
+    );
+
+    metric_header(
+        &mut out,
+        "plate_spinner_db_size_bytes",
+        "gauge",
+        "Size of the plate database",
+    );
+    let _ = writeln!(out, "plate_spinner_db_size_bytes {}", stats.size_bytes);
+
+    metric_header(
+        &mut out,
+        "plate_spinner_websocket_clients",
+        "gauge",
+        "Connected WebSocket clients",
+    );
+    let _ = writeln!(
+        out,
+        "plate_spinner_websocket_clients {}",
+        metrics.websocket_clients.load(Ordering::Relaxed)
+    );
+
+    metrics.health_check.render(
+        &mut out,
+        "plate_spinner_health_check_seconds",
+        "Duration of a stale-status health check pass",
+    );
+
+    out
+}
+
+pub async fn metrics(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
+    let stats = {
+        let db = state.db.lock().unwrap();
+        DbStats {
+            plates_by_status: db.count_plates_by_status()?,
+            size_bytes: db.size_bytes()?,
+        }
+    };
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render(&METRICS, &stats),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let histogram = Histogram::new();
+        histogram.observe(Duration::from_millis(3));
+        histogram.observe(Duration::from_millis(300));
+        histogram.observe(Duration::from_secs(60));
+
+        let mut out = String::new();
+        histogram.render(&mut out, "latency", "test");
+        assert!(out.contains("latency_bucket{le=\"0.001\"} 0\n"));
+        assert!(out.contains("latency_bucket{le=\"0.005\"} 1\n"));
+        assert!(out.contains("latency_bucket{le=\"0.5\"} 2\n"));
+        assert!(out.contains("latency_bucket{le=\"10\"} 2\n"));
+        assert!(out.contains("latency_bucket{le=\"+Inf\"} 3\n"));
+        assert!(out.contains("latency_sum 60.303\n"));
+        assert!(out.contains("latency_count 3\n"));
+    }
+
+    #[test]
+    fn renders_every_status_and_db_stats() {
+        let stats = DbStats {
+            plates_by_status: vec![("running".to_string(), 2)],
+            size_bytes: 4096,
+        };
+        let metrics = Metrics::new();
+        for _ in 0..5 {
+            metrics.record_transition(PlateStatus::Idle);
+        }
+        let out = render(&metrics, &stats);
+        assert!(out.contains("plate_spinner_plates{status=\"running\"} 2\n"));
+        assert!(out.contains("plate_spinner_plates{status=\"closed\"} 0\n"));
+        assert!(out.contains("plate_spinner_transitions_total{to_status=\"idle\"} 5\n"));
+        assert!(out.contains("plate_spinner_transitions_total{to_status=\"error\"} 0\n"));
+        assert!(out.contains("plate_spinner_db_size_bytes 4096\n"));
+        assert!(out.contains("# TYPE plate_spinner_summarizer_seconds histogram\n"));
+    }
+}
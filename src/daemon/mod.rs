@@ -1,33 +1,76 @@
+pub mod api;
+pub mod auth;
+mod backup_task;
+pub mod error;
 pub mod handlers;
 mod health_check;
+pub mod inbox;
+pub mod metrics;
+pub mod remotes;
+pub mod sse;
 pub mod state;
 pub mod summarizer;
+pub mod transcript;
+pub mod webhooks;
 pub mod websocket;
 
+use auth::ApiAuth;
 use axum::{
-    routing::{delete, get, post},
+    middleware,
+    routing::{get, patch, post},
     Router,
 };
 use state::AppState;
 use std::sync::Arc;
 
-pub fn create_router(state: Arc<AppState>) -> Router {
+pub fn create_router(state: Arc<AppState>, auth: Arc<ApiAuth>) -> Router {
     Router::new()
         .route("/health", get(handlers::health))
         .route("/shutdown", post(handlers::shutdown))
         .route("/status", get(handlers::status))
+        .route("/metrics", get(metrics::metrics))
         .route("/events", post(handlers::post_event))
+        .route("/events/stream", get(sse::event_stream))
         .route("/plates", get(handlers::get_plates))
         .route("/plates/register", post(handlers::register_plate))
         .route("/plates/stopped", post(handlers::mark_stopped))
-        .route("/plates/:session_id", delete(handlers::delete_plate))
+        .route(
+            "/plates/:session_id",
+            get(handlers::get_plate).delete(handlers::delete_plate),
+        )
+        .route("/plates/:session_id/digest", get(handlers::get_digest))
+        .route("/plates/:session_id/ack", post(handlers::acknowledge))
+        .route("/usage", get(handlers::get_usage))
+        .route("/inbox", get(inbox::list_items).post(inbox::create_item))
+        .route("/inbox/:id", patch(inbox::update_item))
+        .route("/inbox/:id/dispatch", post(inbox::dispatch_item))
+        .route("/search", get(handlers::search))
         .route("/ws", get(websocket::websocket_handler))
+        .nest("/api/v1", api::routes())
         .with_state(state)
+        .layer(middleware::from_fn_with_state(auth, auth::require_token))
 }
 
 pub async fn run(state: Arc<AppState>, port: u16) -> anyhow::Result<()> {
     health_check::spawn_health_checker(state.clone());
-    let app = create_router(state);
+    backup_task::spawn_backup_task(state.clone());
+    let config = crate::config::load_config();
+    webhooks::spawn_webhook_worker(state.clone(), config.webhooks);
+    state.allow_remote_actions.store(
+        config.allow_remote_actions,
+        std::sync::atomic::Ordering::Relaxed,
+    );
+    remotes::spawn_remote_followers(state.clone(), config.remotes);
+    *state.prices.lock().unwrap() = config.prices;
+    state
+        .summaries
+        .set_limits(summarizer::queue::Limits::from(&config.summarizer));
+    summarizer::queue::spawn_summary_worker(state.clone());
+    let auth = Arc::new(ApiAuth {
+        token: crate::api_token::load_or_create(&crate::api_token::token_path())?,
+        allowed_origins: config.allowed_origins,
+    });
+    let app = create_router(state, auth);
     let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
     axum::serve(listener, app).await?;
     Ok(())
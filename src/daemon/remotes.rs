@@ -0,0 +1,409 @@
+use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
+
+use super::state::{AppState, WsMessage};
+use super::websocket::{ClientMessage, Received, SeqTracker, ServerMessage};
+use crate::config::RemoteConfig;
+use crate::models::Plate;
+
+const RECONNECT_SECS: u64 = 5;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteHealth {
+    pub name: String,
+    pub url: String,
+    pub connected: bool,
+    pub allow_actions: bool,
+    pub plates: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+struct Remote {
+    config: RemoteConfig,
+    connected: bool,
+    allow_actions: bool,
+    last_error: Option<String>,
+    /// Plates as the remote sent them.
+    received: Vec<Plate>,
+    /// The remote's own plates, tagged with its name.
+    plates: Vec<Plate>,
+}
+
+/// Plates mirrored from the daemons listed under `[[remotes]]`.
+#[derive(Default)]
+pub struct Remotes {
+    remotes: Vec<Remote>,
+}
+
+impl Remotes {
+    fn add(&mut self, config: RemoteConfig) -> usize {
+        self.remotes.push(Remote {
+            config,
+            connected: false,
+            allow_actions: false,
+            last_error: None,
+            received: Vec::new(),
+            plates: Vec::new(),
+        });
+        self.remotes.len() - 1
+    }
+
+    pub fn plates(&self) -> Vec<Plate> {
+        self.remotes
+            .iter()
+            .flat_map(|r| r.plates.iter().cloned())
+            .collect()
+    }
+
+    /// The remote a plate lives on, along with the plate.
+    pub fn find(&self, session_id: &str) -> Option<(RemoteConfig, Plate)> {
+        self.remotes.iter().find_map(|remote| {
+            let plate = remote.plates.iter().find(|p| p.session_id == session_id)?;
+            Some((remote.config.clone(), plate.clone()))
+        })
+    }
+
+    /// Remote plates whose project, branch or summary contains `query`.
+    pub fn search(&self, query: &str) -> Vec<Plate> {
+        let query = query.to_lowercase();
+        let matches =
+            |field: Option<&str>| field.is_some_and(|f| f.to_lowercase().contains(&query));
+        self.remotes
+            .iter()
+            .flat_map(|r| &r.plates)
+            .filter(|p| {
+                matches(Some(&p.project_path))
+                    || matches(p.git_branch.as_deref())
+                    || matches(p.summary.as_deref())
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn health(&self) -> Vec<RemoteHealth> {
+        self.remotes
+            .iter()
+            .map(|r| RemoteHealth {
+                name: r.config.name.clone(),
+                url: r.config.url.clone(),
+                connected: r.connected,
+                allow_actions: r.allow_actions,
+                plates: r.plates.len(),
+                last_error: r.last_error.clone(),
+            })
+            .collect()
+    }
+
+    fn connected(&mut self, index: usize, allow_actions: bool) {
+        let remote = &mut self.remotes[index];
+        remote.connected = true;
+        remote.allow_actions = allow_actions;
+        remote.last_error = None;
+    }
+
+    /// Forgets a remote's plates once its connection is gone, returning
+    /// their ids.
+    fn disconnected(&mut self, index: usize, error: Option<String>) -> Vec<String> {
+        let remote = &mut self.remotes[index];
+        remote.connected = false;
+        remote.last_error = error;
+        remote.received.clear();
+        std::mem::take(&mut remote.plates)
+            .into_iter()
+            .map(|p| p.session_id)
+            .collect()
+    }
+
+    /// Applies a message from a remote, returning the local notifications
+    /// for whatever changed.
+    fn apply(&mut self, index: usize, msg: ServerMessage) -> Vec<WsMessage> {
+        let remote = &mut self.remotes[index];
+        msg.apply(&mut remote.received);
+
+        // Plates the remote itself aggregated are left to their own daemon,
+        // so two daemons listing each other don't echo plates back and forth.
+        let plates: Vec<Plate> = remote
+            .received
+            .iter()
+            .filter(|p| p.host.is_none())
+            .cloned()
+            .map(|mut p| {
+                p.host = Some(remote.config.name.clone());
+                p.read_only = !remote.allow_actions;
+                p
+            })
+            .collect();
+
+        let mut before: HashMap<String, serde_json::Value> = remote
+            .plates
+            .iter()
+            .map(|p| {
+                (
+                    p.session_id.clone(),
+                    serde_json::to_value(p).unwrap_or_default(),
+                )
+            })
+            .collect();
+        let mut changes = Vec::new();
+        for plate in &plates {
+            let previous = before.remove(&plate.session_id);
+            if previous != serde_json::to_value(plate).ok() {
+                changes.push(WsMessage::PlateUpdate(plate.session_id.clone()));
+            }
+        }
+        changes.extend(before.into_keys().map(WsMessage::PlateDeleted));
+
+        remote.plates = plates;
+        changes
+    }
+}
+
+fn auth_headers(config: &RemoteConfig) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(value) = config
+        .token
+        .as_ref()
+        .and_then(|t| HeaderValue::from_str(&format!("Bearer {}", t)).ok())
+    {
+        headers.insert(AUTHORIZATION, value);
+    }
+    headers
+}
+
+/// HTTP client for a remote daemon, with its token attached.
+pub fn remote_client(config: &RemoteConfig) -> reqwest::Client {
+    reqwest::Client::builder()
+        .default_headers(auth_headers(config))
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .unwrap_or_default()
+}
+
+pub fn remote_url(config: &RemoteConfig, path: &str) -> String {
+    format!("{}{}", config.url.trim_end_matches('/'), path)
+}
+
+async fn follow_once(state: &AppState, index: usize, config: &RemoteConfig) -> Result<()> {
+    let status: serde_json::Value = remote_client(config)
+        .get(remote_url(config, "/status"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let allow_actions = status["remote_actions"].as_bool().unwrap_or(false);
+
+    let ws_url = remote_url(config, "/ws")
+        .replacen("https://", "wss://", 1)
+        .replacen("http://", "ws://", 1);
+    let mut request = ws_url.as_str().into_client_request()?;
+    request.headers_mut().extend(auth_headers(config));
+    let (ws_stream, _) = tokio_tungstenite::connect_async(request).await?;
+    let (mut write, mut read) = ws_stream.split();
+    state
+        .remotes
+        .lock()
+        .unwrap()
+        .connected(index, allow_actions);
+
+    let resync = serde_json::to_string(&ClientMessage::Resync)?;
+    let mut tracker = SeqTracker::default();
+    while let Some(msg) = read.next().await {
+        let Message::Text(text) = msg? else {
+            continue;
+        };
+        let Ok(msg) = serde_json::from_str::<ServerMessage>(&text) else {
+            continue;
+        };
+        match tracker.receive(&msg) {
+            Received::Apply => {}
+            Received::Skip => continue,
+            Received::Resync => {
+                write.send(Message::Text(resync.clone())).await?;
+                continue;
+            }
+        }
+
+        let changes = state.remotes.lock().unwrap().apply(index, msg);
+        for change in changes {
+            let _ = state.tx.send(change);
+        }
+    }
+    anyhow::bail!("Connection closed")
+}
+
+async fn follow(state: Arc<AppState>, index: usize, config: RemoteConfig) {
+    loop {
+        let error = follow_once(&state, index, &config)
+            .await
+            .err()
+            .map(|e| e.to_string());
+        let removed = state.remotes.lock().unwrap().disconnected(index, error);
+        for session_id in removed {
+            let _ = state.tx.send(WsMessage::PlateDeleted(session_id));
+        }
+        tokio::time::sleep(Duration::from_secs(RECONNECT_SECS)).await;
+    }
+}
+
+/// Follows every configured remote over its WebSocket, reconnecting when
+/// the connection drops.
+pub fn spawn_remote_followers(state: Arc<AppState>, remotes: Vec<RemoteConfig>) {
+    for config in remotes {
+        let index = state.remotes.lock().unwrap().add(config.clone());
+        tokio::spawn(follow(state.clone(), index, config));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::auth::ApiAuth;
+    use crate::daemon::{create_router, handlers};
+    use crate::db::Database;
+    use axum::extract::{Path as UrlPath, State};
+    use std::path::Path;
+    use std::sync::atomic::Ordering;
+
+    const TOKEN: &str = "remote-token";
+
+    fn app_state() -> Arc<AppState> {
+        Arc::new(AppState::new(
+            Database::open(Path::new(":memory:")).unwrap(),
+        ))
+    }
+
+    fn record(state: &AppState, session_id: &str, status: &str) {
+        let db = state.db.lock().unwrap();
+        db.upsert_plate(
+            session_id,
+            "/work/api",
+            None,
+            None,
+            None,
+            status,
+            "stop",
+            None,
+            "2026-01-01T00:00:00+00:00",
+        )
+        .unwrap();
+    }
+
+    /// Serves `state` as a full daemon on a loopback port.
+    async fn serve(state: Arc<AppState>) -> String {
+        let auth = Arc::new(ApiAuth {
+            token: TOKEN.to_string(),
+            allowed_origins: Vec::new(),
+        });
+        let app = create_router(state, auth);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    /// Starts a remote daemon with one plate and a local daemon following it.
+    async fn pair(allow_actions: bool) -> (Arc<AppState>, Arc<AppState>) {
+        let remote = app_state();
+        remote
+            .allow_remote_actions
+            .store(allow_actions, Ordering::Relaxed);
+        record(&remote, "remote-plate", "idle");
+        let url = serve(remote.clone()).await;
+
+        let local = app_state();
+        record(&local, "local-plate", "running");
+        spawn_remote_followers(
+            local.clone(),
+            vec![RemoteConfig {
+                name: "vm1".to_string(),
+                url,
+                token: Some(TOKEN.to_string()),
+            }],
+        );
+        (local, remote)
+    }
+
+    async fn wait_for(state: &AppState, check: impl Fn(&[Plate]) -> bool) -> Vec<Plate> {
+        for _ in 0..100 {
+            let plates = state.all_plates().unwrap();
+            if check(&plates) {
+                return plates;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("timed out waiting for remote plates");
+    }
+
+    #[tokio::test]
+    async fn merges_remote_plates_and_follows_changes() {
+        let (local, remote) = pair(false).await;
+
+        let plates = wait_for(&local, |p| p.len() == 2).await;
+        let mirrored = plates
+            .iter()
+            .find(|p| p.session_id == "remote-plate")
+            .unwrap();
+        assert_eq!(mirrored.host.as_deref(), Some("vm1"));
+        assert!(mirrored.read_only);
+        let own = plates
+            .iter()
+            .find(|p| p.session_id == "local-plate")
+            .unwrap();
+        assert_eq!(own.host, None);
+
+        record(&remote, "remote-plate", "awaiting_input");
+        let _ = remote
+            .tx
+            .send(WsMessage::PlateUpdate("remote-plate".to_string()));
+        wait_for(&local, |plates| {
+            plates
+                .iter()
+                .any(|p| p.session_id == "remote-plate" && p.status.needs_attention())
+        })
+        .await;
+
+        let health = local.remotes.lock().unwrap().health();
+        assert!(health[0].connected);
+        assert_eq!(health[0].plates, 1);
+    }
+
+    #[tokio::test]
+    async fn remote_plates_are_read_only_unless_allowed() {
+        let (local, remote) = pair(false).await;
+        wait_for(&local, |p| p.len() == 2).await;
+        let err = handlers::delete_plate(State(local.clone()), UrlPath("remote-plate".into()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, "read_only");
+        assert!(remote
+            .db
+            .lock()
+            .unwrap()
+            .get_plate("remote-plate")
+            .unwrap()
+            .is_some());
+
+        let (local, remote) = pair(true).await;
+        wait_for(&local, |p| p.len() == 2).await;
+        let deleted =
+            handlers::delete_plate(State(local.clone()), UrlPath("remote-plate".into())).await;
+        assert!(deleted.is_ok());
+        assert!(remote
+            .db
+            .lock()
+            .unwrap()
+            .get_plate("remote-plate")
+            .unwrap()
+            .is_none());
+        wait_for(&local, |p| p.len() == 1).await;
+    }
+}
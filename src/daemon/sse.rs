@@ -0,0 +1,211 @@
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{stream, Stream};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use super::error::ApiError;
+use super::state::{AppState, WsMessage};
+use crate::db::Transition;
+
+const BATCH_SIZE: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct StreamParams {
+    filter: Option<String>,
+}
+
+struct Follower {
+    state: Arc<AppState>,
+    rx: broadcast::Receiver<WsMessage>,
+    last_id: i64,
+    attention_only: bool,
+    pending: VecDeque<Transition>,
+}
+
+impl Follower {
+    /// Reads the next batch of transitions, returning how many rows it
+    /// read before filtering.
+    fn fetch(&mut self) -> usize {
+        let db = self.state.db.lock().unwrap();
+        let Ok(fresh) = db.get_transitions_after(self.last_id, BATCH_SIZE) else {
+            return 0;
+        };
+        let read = fresh.len();
+        if let Some(last) = fresh.last() {
+            self.last_id = last.id;
+        }
+        self.pending.extend(
+            fresh
+                .into_iter()
+                .filter(|t| !self.attention_only || t.needs_attention()),
+        );
+        read
+    }
+
+    /// A `transition` event whose id is the transition id, so
+    /// `Last-Event-ID` resumes right after it. `None` when the plate can't
+    /// be read; an error item would end the stream.
+    fn event(&self, transition: Transition) -> Option<Result<Event, axum::Error>> {
+        let id = transition.id.to_string();
+        let change = self.state.db.lock().unwrap().status_change(transition);
+        let change = match change {
+            Ok(change) => change,
+            Err(e) => {
+                eprintln!("Event stream: skipping transition {}: {}", id, e);
+                return None;
+            }
+        };
+        Some(
+            Event::default()
+                .id(id)
+                .event("transition")
+                .json_data(change),
+        )
+    }
+
+    async fn next(mut self) -> Option<(Result<Event, axum::Error>, Self)> {
+        loop {
+            if let Some(transition) = self.pending.pop_front() {
+                if let Some(event) = self.event(transition) {
+                    return Some((event, self));
+                }
+                continue;
+            }
+
+            // A full batch may have been filtered down to nothing, with
+            // more backlog behind it
+            if self.fetch() == BATCH_SIZE || !self.pending.is_empty() {
+                continue;
+            }
+
+            // Any plate activity may have produced a transition; the table
+            // is the source of truth, so lagging here loses nothing.
+            match self.rx.recv().await {
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+fn last_event_id(headers: &HeaderMap) -> Option<i64> {
+    headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+pub async fn event_stream(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    params: Result<Query<StreamParams>, QueryRejection>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let Query(params) = params.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let attention_only = match params.filter.as_deref() {
+        None => false,
+        Some("needs_attention") => true,
+        Some(other) => {
+            return Err(ApiError::bad_request(format!(
+                "Unknown filter: {} (use needs_attention)",
+                other
+            )))
+        }
+    };
+
+    // Subscribe before reading the latest id so nothing slips in between
+    let rx = state.tx.subscribe();
+    let last_id = match last_event_id(&headers) {
+        Some(id) => id,
+        None => state.db.lock().unwrap().latest_transition_id()?,
+    };
+
+    let follower = Follower {
+        state,
+        rx,
+        last_id,
+        attention_only,
+        pending: VecDeque::new(),
+    };
+    Ok(Sse::new(stream::unfold(follower, Follower::next)).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn reads_last_event_id_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(last_event_id(&headers), None);
+        headers.insert("Last-Event-ID", HeaderValue::from_static("42"));
+        assert_eq!(last_event_id(&headers), Some(42));
+        headers.insert("Last-Event-ID", HeaderValue::from_static("nope"));
+        assert_eq!(last_event_id(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn attention_filter_reads_past_a_batch_of_other_transitions() {
+        let state = Arc::new(AppState::new(
+            crate::db::Database::open(std::path::Path::new(":memory:")).unwrap(),
+        ));
+        {
+            let db = state.db.lock().unwrap();
+            let statuses = (0..=BATCH_SIZE)
+                .map(|i| if i % 2 == 0 { "running" } else { "starting" })
+                .chain(["idle"]);
+            for status in statuses {
+                db.upsert_plate(
+                    "a",
+                    "/work/api",
+                    None,
+                    None,
+                    None,
+                    status,
+                    "stop",
+                    None,
+                    "2026-01-01T00:00:00+00:00",
+                )
+                .unwrap();
+            }
+        }
+        let latest = state.db.lock().unwrap().latest_transition_id().unwrap();
+        assert!(latest > BATCH_SIZE as i64 + 1);
+
+        let follower = Follower {
+            rx: state.tx.subscribe(),
+            state,
+            last_id: 0,
+            attention_only: true,
+            pending: VecDeque::new(),
+        };
+        let (event, follower) =
+            tokio::time::timeout(std::time::Duration::from_secs(5), follower.next())
+                .await
+                .expect("follower waited instead of reading the backlog")
+                .unwrap();
+        assert!(event.is_ok());
+        assert_eq!(follower.last_id, latest);
+    }
+
+    #[test]
+    fn attention_filter_matches_waiting_statuses() {
+        let transition = |to: &str| Transition {
+            id: 1,
+            session_id: "a".to_string(),
+            from_status: Some("running".to_string()),
+            to_status: to.to_string(),
+            created_at: "2026-01-01T00:00:00+00:00".to_string(),
+        };
+        assert!(transition("awaiting_input").needs_attention());
+        assert!(transition("idle").needs_attention());
+        assert!(!transition("running").needs_attention());
+        assert!(!transition("closed").needs_attention());
+    }
+}
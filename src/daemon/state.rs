@@ -1,4 +1,12 @@
+use super::remotes::Remotes;
+use super::summarizer::health::FailureTracker;
+use super::summarizer::queue::SummaryQueue;
+use super::transcript::TranscriptCache;
+use crate::backup::DbHealth;
+use crate::config::Prices;
 use crate::db::Database;
+use crate::models::Plate;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use tokio::sync::broadcast;
 
@@ -6,11 +14,21 @@ use tokio::sync::broadcast;
 pub enum WsMessage {
     PlateUpdate(String),
     PlateDeleted(String),
+    InboxUpdate,
 }
 
 pub struct AppState {
     pub db: Mutex<Database>,
     pub tx: broadcast::Sender<WsMessage>,
+    pub db_health: Mutex<DbHealth>,
+    pub remotes: Mutex<Remotes>,
+    pub summaries: SummaryQueue,
+    pub summarizer_failures: FailureTracker,
+    pub transcripts: TranscriptCache,
+    /// Prices token usage is recorded at, from `[prices]`.
+    pub prices: Mutex<Prices>,
+    /// Whether aggregating daemons may act on our plates (see `/status`).
+    pub allow_remote_actions: AtomicBool,
 }
 
 impl AppState {
@@ -19,6 +37,44 @@ impl AppState {
         Self {
             db: Mutex::new(db),
             tx,
+            db_health: Mutex::new(DbHealth::default()),
+            remotes: Mutex::new(Remotes::default()),
+            summaries: SummaryQueue::default(),
+            summarizer_failures: FailureTracker::default(),
+            transcripts: TranscriptCache::default(),
+            prices: Mutex::new(Prices::default()),
+            allow_remote_actions: AtomicBool::new(false),
         }
     }
+
+    pub fn with_db_health(db: Database, db_health: DbHealth) -> Self {
+        let state = Self::new(db);
+        *state.db_health.lock().unwrap() = db_health;
+        state
+    }
+
+    pub fn allows_remote_actions(&self) -> bool {
+        self.allow_remote_actions.load(Ordering::Relaxed)
+    }
+
+    /// Local plates followed by those of every connected remote, most
+    /// recently updated first.
+    pub fn all_plates(&self) -> anyhow::Result<Vec<Plate>> {
+        let mut plates = self.db.lock().unwrap().get_plates()?;
+        plates.extend(self.remotes.lock().unwrap().plates());
+        plates.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(plates)
+    }
+
+    pub fn find_plate(&self, session_id: &str) -> anyhow::Result<Option<Plate>> {
+        if let Some(plate) = self.db.lock().unwrap().get_plate(session_id)? {
+            return Ok(Some(plate));
+        }
+        Ok(self
+            .remotes
+            .lock()
+            .unwrap()
+            .find(session_id)
+            .map(|(_, plate)| plate))
+    }
 }
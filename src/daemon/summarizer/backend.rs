@@ -0,0 +1,595 @@
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::config::{SummarizerBackend, SummarizerConfig};
+
+const ANTHROPIC_URL: &str = "https://api.anthropic.com";
+const ANTHROPIC_MODEL: &str = "claude-3-5-haiku-latest";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const OPENAI_URL: &str = "https://api.openai.com/v1";
+const OPENAI_MODEL: &str = "gpt-4o-mini";
+
+/// Why a summarizer call failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The key was missing, invalid or not allowed to use the model.
+    Auth,
+    RateLimit,
+    /// The service couldn't be reached, timed out or had a server error.
+    Network,
+    /// The reply couldn't be used.
+    BadResponse,
+}
+
+impl FailureKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureKind::Auth => "auth",
+            FailureKind::RateLimit => "rate_limit",
+            FailureKind::Network => "network",
+            FailureKind::BadResponse => "bad_response",
+        }
+    }
+}
+
+/// A failed summarizer call, with a short message such as "401 invalid
+/// x-api-key".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub kind: FailureKind,
+    pub message: String,
+    /// How long the service asked us to wait, from `Retry-After`.
+    pub retry_after: Option<Duration>,
+}
+
+impl Failure {
+    pub fn new(kind: FailureKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            retry_after: None,
+        }
+    }
+
+    fn network(e: reqwest::Error) -> Self {
+        let message = if e.is_timeout() {
+            "timed out".to_string()
+        } else if e.is_connect() {
+            "connection failed".to_string()
+        } else {
+            e.to_string()
+        };
+        Self::new(FailureKind::Network, message)
+    }
+
+    /// Classifies an error status, taking the message from an
+    /// `{"error": {"message": ...}}` body when there is one.
+    fn from_status(status: StatusCode, body: &str, retry_after: Option<Duration>) -> Self {
+        let kind = match status.as_u16() {
+            401 | 403 => FailureKind::Auth,
+            // 529 is Anthropic's "overloaded"
+            429 | 529 => FailureKind::RateLimit,
+            500..=599 => FailureKind::Network,
+            _ => FailureKind::BadResponse,
+        };
+        let detail = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| v["error"]["message"].as_str().map(str::to_string))
+            .or_else(|| status.canonical_reason().map(str::to_string))
+            .unwrap_or_else(|| body.chars().take(100).collect());
+        Self {
+            kind,
+            message: format!("{} {}", status.as_u16(), detail),
+            retry_after,
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.kind.as_str())
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// A model that turns a prompt into a short reply.
+pub trait Summarizer: Send + Sync {
+    /// Sends `prompt` and returns the reply, trimmed.
+    fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String, Failure>;
+}
+
+/// Builds the summarizer described by `config`, or `None` when it can't
+/// work: no API key for Anthropic, or no command for the command backend.
+pub fn from_config(config: &SummarizerConfig) -> Option<Box<dyn Summarizer>> {
+    let timeout = Duration::from_secs(config.timeout_secs);
+    match config.backend {
+        SummarizerBackend::Anthropic => Some(Box::new(Anthropic {
+            http: Http::new(config, ANTHROPIC_URL, ANTHROPIC_MODEL),
+            api_key: super::get_api_key()?,
+        })),
+        SummarizerBackend::Openai => Some(Box::new(OpenAi {
+            http: Http::new(config, OPENAI_URL, OPENAI_MODEL),
+        })),
+        SummarizerBackend::Command => Some(Box::new(ShellCommand {
+            command: config.command.clone()?,
+            timeout,
+        })),
+    }
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+/// Request body shared by both APIs.
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    messages: Vec<Message<'a>>,
+}
+
+struct Http {
+    client: reqwest::blocking::Client,
+    base_url: String,
+    model: String,
+    headers: HashMap<String, String>,
+}
+
+impl Http {
+    fn new(config: &SummarizerConfig, default_url: &str, default_model: &str) -> Self {
+        Self {
+            client: reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(config.timeout_secs))
+                .build()
+                .unwrap_or_default(),
+            base_url: config
+                .base_url
+                .as_deref()
+                .unwrap_or(default_url)
+                .trim_end_matches('/')
+                .to_string(),
+            model: config
+                .model
+                .clone()
+                .unwrap_or_else(|| default_model.to_string()),
+            headers: config.headers.clone(),
+        }
+    }
+
+    fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        prompt: &str,
+        max_tokens: u32,
+        extra_headers: &[(&str, &str)],
+    ) -> Result<T, Failure> {
+        let request = ChatRequest {
+            model: &self.model,
+            max_tokens,
+            messages: vec![Message {
+                role: "user",
+                content: prompt,
+            }],
+        };
+        let mut builder = self
+            .client
+            .post(format!("{}{}", self.base_url, path))
+            .header("content-type", "application/json")
+            .json(&request);
+        for (name, value) in extra_headers {
+            builder = builder.header(*name, *value);
+        }
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send().map_err(Failure::network)?;
+        check_status(response)?
+            .json()
+            .map_err(|e| Failure::new(FailureKind::BadResponse, format!("unexpected body: {}", e)))
+    }
+}
+
+fn check_status(
+    response: reqwest::blocking::Response,
+) -> Result<reqwest::blocking::Response, Failure> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let retry_after = response
+        .headers()
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .map(Duration::from_secs);
+    let body = response.text().unwrap_or_default();
+    Err(Failure::from_status(status, &body, retry_after))
+}
+
+/// Checks an Anthropic key by listing models, which costs nothing.
+pub fn verify_anthropic_key(config: &SummarizerConfig, api_key: &str) -> Result<(), Failure> {
+    let http = Http::new(config, ANTHROPIC_URL, ANTHROPIC_MODEL);
+    let response = http
+        .client
+        .get(format!("{}/v1/models?limit=1", http.base_url))
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .send()
+        .map_err(Failure::network)?;
+    check_status(response).map(|_| ())
+}
+
+struct Anthropic {
+    http: Http,
+    api_key: String,
+}
+
+#[derive(Deserialize)]
+struct AnthropicResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Deserialize)]
+struct ContentBlock {
+    text: Option<String>,
+}
+
+impl Summarizer for Anthropic {
+    fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String, Failure> {
+        let response: AnthropicResponse = self.http.post(
+            "/v1/messages",
+            prompt,
+            max_tokens,
+            &[
+                ("x-api-key", &self.api_key),
+                ("anthropic-version", ANTHROPIC_VERSION),
+            ],
+        )?;
+        response
+            .content
+            .into_iter()
+            .find_map(|block| block.text)
+            .map(|text| text.trim().to_string())
+            .ok_or_else(|| Failure::new(FailureKind::BadResponse, "reply has no text"))
+    }
+}
+
+struct OpenAi {
+    http: Http,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChoiceMessage {
+    content: Option<String>,
+}
+
+impl Summarizer for OpenAi {
+    fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String, Failure> {
+        let response: OpenAiResponse =
+            self.http
+                .post("/chat/completions", prompt, max_tokens, &[])?;
+        response
+            .choices
+            .into_iter()
+            .find_map(|choice| choice.message.content)
+            .map(|text| text.trim().to_string())
+            .ok_or_else(|| Failure::new(FailureKind::BadResponse, "reply has no content"))
+    }
+}
+
+/// Runs `sh -c <command>` with the prompt on stdin.
+struct ShellCommand {
+    command: String,
+    timeout: Duration,
+}
+
+impl Summarizer for ShellCommand {
+    fn complete(&self, prompt: &str, _max_tokens: u32) -> Result<String, Failure> {
+        let failed = |message: String| Failure::new(FailureKind::BadResponse, message);
+        let mut child = Command::new("sh")
+            .args(["-c", &self.command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| failed(format!("failed to run {}: {}", self.command, e)))?;
+
+        // Write from a thread so a command that doesn't read its input
+        // can't block us past the timeout.
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| failed("no stdin".into()))?;
+        let input = prompt.to_string();
+        std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| failed("no stdout".into()))?;
+        let reader = std::thread::spawn(move || {
+            let mut out = String::new();
+            stdout.read_to_string(&mut out).map(|_| out)
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(|e| failed(e.to_string()))? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Failure::new(
+                    FailureKind::Network,
+                    format!("{} timed out after {:?}", self.command, self.timeout),
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        if !status.success() {
+            return Err(failed(format!("{} exited with {}", self.command, status)));
+        }
+
+        let out = reader
+            .join()
+            .map_err(|_| failed("output reader panicked".into()))?
+            .map_err(|e| failed(e.to_string()))?;
+        let out = out.trim();
+        if out.is_empty() {
+            return Err(failed(format!("{} printed nothing", self.command)));
+        }
+        Ok(out.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        http::HeaderMap,
+        routing::{any, post},
+        Json, Router,
+    };
+    use std::sync::{Arc, Mutex};
+
+    type Seen = Arc<Mutex<Vec<(HeaderMap, serde_json::Value)>>>;
+
+    /// Serves `reply` at `path` on a loopback port, recording requests.
+    async fn mock(path: &'static str, reply: serde_json::Value) -> (String, Seen) {
+        let seen: Seen = Arc::default();
+        let recorded = seen.clone();
+        let app = Router::new().route(
+            path,
+            post(
+                move |headers: HeaderMap, Json(body): Json<serde_json::Value>| {
+                    recorded.lock().unwrap().push((headers, body));
+                    let reply = reply.clone();
+                    async move { Json(reply) }
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, seen)
+    }
+
+    /// Answers every request to `path` with `status` and `body`.
+    async fn failing(path: &'static str, status: u16, body: &'static str) -> String {
+        let app = Router::new().route(
+            path,
+            any(move || async move {
+                (
+                    axum::http::StatusCode::from_u16(status).unwrap(),
+                    [("retry-after", "7")],
+                    body,
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    fn config(backend: SummarizerBackend, base_url: &str) -> SummarizerConfig {
+        SummarizerConfig {
+            backend,
+            model: Some("test-model".to_string()),
+            base_url: Some(base_url.to_string()),
+            headers: HashMap::from([("X-Team".to_string(), "infra".to_string())]),
+            ..Default::default()
+        }
+    }
+
+    /// Builds and calls the summarizer off the runtime: the blocking HTTP
+    /// client can't be created or dropped inside async code.
+    async fn complete(
+        build: impl FnOnce() -> Box<dyn Summarizer> + Send + 'static,
+    ) -> Result<String, Failure> {
+        tokio::task::spawn_blocking(move || build().complete("What now?", 30))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn anthropic_posts_messages_with_key_and_headers() {
+        let (url, seen) = mock(
+            "/v1/messages",
+            serde_json::json!({"content": [{"type": "text", "text": " Auth: testing \n"}]}),
+        )
+        .await;
+        let config = config(SummarizerBackend::Anthropic, &url);
+        let reply = complete(move || {
+            Box::new(Anthropic {
+                http: Http::new(&config, ANTHROPIC_URL, ANTHROPIC_MODEL),
+                api_key: "sk-test".to_string(),
+            })
+        })
+        .await;
+
+        assert_eq!(reply.unwrap(), "Auth: testing");
+        let seen = seen.lock().unwrap();
+        let (headers, body) = &seen[0];
+        assert_eq!(headers["x-api-key"], "sk-test");
+        assert_eq!(headers["x-team"], "infra");
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["messages"][0]["content"], "What now?");
+    }
+
+    #[tokio::test]
+    async fn openai_compatible_reads_first_choice() {
+        let (url, seen) = mock(
+            "/chat/completions",
+            serde_json::json!({"choices": [{"message": {"role": "assistant", "content": "Fixing CI"}}]}),
+        )
+        .await;
+        let mut config = config(SummarizerBackend::Openai, &format!("{}/", url));
+        config
+            .headers
+            .insert("Authorization".to_string(), "Bearer local".to_string());
+
+        let reply = complete(move || from_config(&config).unwrap()).await;
+        assert_eq!(reply.unwrap(), "Fixing CI");
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0].0["authorization"], "Bearer local");
+        assert_eq!(seen[0].1["max_tokens"], 30);
+    }
+
+    #[tokio::test]
+    async fn http_errors_and_empty_replies_fail() {
+        let (url, _) = mock("/chat/completions", serde_json::json!({"choices": []})).await;
+        let empty = config(SummarizerBackend::Openai, &url);
+        assert!(complete(move || from_config(&empty).unwrap())
+            .await
+            .is_err());
+
+        let unreachable = config(SummarizerBackend::Openai, "http://127.0.0.1:9/nope");
+        assert!(complete(move || from_config(&unreachable).unwrap())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn failures_are_classified() {
+        let cases = [
+            (
+                401,
+                r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#,
+                FailureKind::Auth,
+                "401 invalid x-api-key",
+            ),
+            (429, "", FailureKind::RateLimit, "429 Too Many Requests"),
+            (503, "down", FailureKind::Network, "503 Service Unavailable"),
+            (400, "{}", FailureKind::BadResponse, "400 Bad Request"),
+        ];
+        for (status, body, kind, message) in cases {
+            let url = failing("/chat/completions", status, body).await;
+            let config = config(SummarizerBackend::Openai, &url);
+            let failure = complete(move || from_config(&config).unwrap())
+                .await
+                .unwrap_err();
+            assert_eq!(failure.kind, kind);
+            assert_eq!(failure.message, message);
+            assert_eq!(failure.retry_after, Some(Duration::from_secs(7)));
+        }
+
+        let url = failing("/chat/completions", 200, "not json").await;
+        let garbled = config(SummarizerBackend::Openai, &url);
+        let failure = complete(move || from_config(&garbled).unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(failure.kind, FailureKind::BadResponse);
+
+        let unreachable = config(SummarizerBackend::Openai, "http://127.0.0.1:9");
+        let failure = complete(move || from_config(&unreachable).unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(failure.kind, FailureKind::Network);
+    }
+
+    #[tokio::test]
+    async fn verifies_anthropic_keys() {
+        let rejected = config(
+            SummarizerBackend::Anthropic,
+            &failing(
+                "/v1/models",
+                401,
+                r#"{"error":{"message":"invalid x-api-key"}}"#,
+            )
+            .await,
+        );
+        let accepted = config(
+            SummarizerBackend::Anthropic,
+            &failing("/v1/models", 200, r#"{"data":[]}"#).await,
+        );
+        let (rejected, accepted) = tokio::task::spawn_blocking(move || {
+            (
+                verify_anthropic_key(&rejected, "sk-bad"),
+                verify_anthropic_key(&accepted, "sk-good"),
+            )
+        })
+        .await
+        .unwrap();
+        assert_eq!(rejected.unwrap_err().kind, FailureKind::Auth);
+        assert!(accepted.is_ok());
+    }
+
+    #[test]
+    fn command_reads_prompt_from_stdin() {
+        let summarizer = ShellCommand {
+            command: "tr a-z A-Z".to_string(),
+            timeout: Duration::from_secs(5),
+        };
+        assert_eq!(summarizer.complete("fix tests", 30).unwrap(), "FIX TESTS");
+
+        let failing = ShellCommand {
+            command: "exit 3".to_string(),
+            timeout: Duration::from_secs(5),
+        };
+        assert!(failing.complete("x", 30).is_err());
+    }
+
+    #[test]
+    fn command_is_killed_after_timeout() {
+        let summarizer = ShellCommand {
+            command: "sleep 5".to_string(),
+            timeout: Duration::from_millis(100),
+        };
+        let started = Instant::now();
+        assert_eq!(
+            summarizer.complete("x", 30).unwrap_err().kind,
+            FailureKind::Network
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn command_backend_needs_a_command() {
+        let config = SummarizerConfig {
+            backend: SummarizerBackend::Command,
+            ..Default::default()
+        };
+        assert!(from_config(&config).is_none());
+    }
+}
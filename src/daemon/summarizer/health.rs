@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use super::backend::{Failure, FailureKind};
+
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 30 * 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureRecord {
+    pub kind: FailureKind,
+    pub message: String,
+    pub at: String,
+}
+
+/// How the model has been answering, reported under `summarizer` in
+/// `/status`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SummarizerHealth {
+    pub last_failure: Option<FailureRecord>,
+    pub last_success_at: Option<String>,
+    /// Failures since the last success.
+    pub consecutive_failures: u32,
+    /// The model isn't called again before this; summaries are built from
+    /// the transcript meanwhile.
+    pub backoff_until: Option<String>,
+}
+
+impl SummarizerHealth {
+    /// The failure to show, if the model hasn't answered since it.
+    pub fn current_failure(&self) -> Option<&FailureRecord> {
+        (self.consecutive_failures > 0)
+            .then_some(self.last_failure.as_ref())
+            .flatten()
+    }
+}
+
+/// Delay before calling the model again after `failures` failures in a
+/// row: 30s, 60s, 120s... capped at 30 minutes.
+fn backoff_secs(failures: u32) -> i64 {
+    BASE_BACKOFF_SECS
+        .saturating_mul(1i64 << failures.saturating_sub(1).min(20))
+        .min(MAX_BACKOFF_SECS)
+}
+
+/// Records model failures and backs off after them.
+#[derive(Default)]
+pub struct FailureTracker {
+    health: Mutex<SummarizerHealth>,
+}
+
+impl FailureTracker {
+    pub fn record_success(&self, now: &str) {
+        let mut health = self.health.lock().unwrap();
+        health.last_success_at = Some(now.to_string());
+        health.consecutive_failures = 0;
+        health.backoff_until = None;
+    }
+
+    pub fn record_failure(&self, failure: &Failure, now: chrono::DateTime<chrono::Utc>) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures += 1;
+        let mut delay = backoff_secs(health.consecutive_failures);
+        if let Some(retry_after) = failure.retry_after {
+            delay = delay.max(retry_after.as_secs().min(MAX_BACKOFF_SECS as u64) as i64);
+        }
+        health.backoff_until = Some((now + chrono::Duration::seconds(delay)).to_rfc3339());
+        health.last_failure = Some(FailureRecord {
+            kind: failure.kind,
+            message: failure.message.clone(),
+            at: now.to_rfc3339(),
+        });
+    }
+
+    /// Whether the model should be left alone at `now`.
+    pub fn backing_off(&self, now: &str) -> bool {
+        self.health
+            .lock()
+            .unwrap()
+            .backoff_until
+            .as_deref()
+            .is_some_and(|until| now < until)
+    }
+
+    pub fn health(&self) -> SummarizerHealth {
+        self.health.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn at(secs: i64) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::from_timestamp(1_767_225_600 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn backs_off_exponentially_until_a_success() {
+        let tracker = FailureTracker::default();
+        let failure = Failure::new(FailureKind::Auth, "401 invalid x-api-key");
+        tracker.record_failure(&failure, at(0));
+        assert!(tracker.backing_off(&at(29).to_rfc3339()));
+        assert!(!tracker.backing_off(&at(30).to_rfc3339()));
+
+        tracker.record_failure(&failure, at(30));
+        assert!(tracker.backing_off(&at(89).to_rfc3339()));
+        let health = tracker.health();
+        assert_eq!(health.consecutive_failures, 2);
+        assert_eq!(
+            health.current_failure().unwrap().message,
+            "401 invalid x-api-key"
+        );
+
+        tracker.record_success(&at(100).to_rfc3339());
+        let health = tracker.health();
+        assert!(health.current_failure().is_none());
+        assert!(health.last_failure.is_some());
+        assert!(!tracker.backing_off(&at(100).to_rfc3339()));
+    }
+
+    #[test]
+    fn honours_retry_after() {
+        let tracker = FailureTracker::default();
+        let failure = Failure {
+            retry_after: Some(Duration::from_secs(120)),
+            ..Failure::new(FailureKind::RateLimit, "429 Too Many Requests")
+        };
+        tracker.record_failure(&failure, at(0));
+        assert!(tracker.backing_off(&at(119).to_rfc3339()));
+        assert_eq!(backoff_secs(30), MAX_BACKOFF_SECS);
+    }
+}
@@ -0,0 +1,210 @@
+use super::SummaryResult;
+use crate::daemon::transcript::{ToolUse, Transcript};
+use crate::models::Todo;
+
+const GOAL_WORDS: usize = 5;
+const ACTIVITY_WORDS: usize = 8;
+
+/// Builds a "Goal: activity" line without a model. The goal is the cached
+/// one or the start of the first prompt; the activity is the in-progress
+/// todo, else whichever of the last tool call and the last assistant
+/// sentence came later.
+pub fn summarize(
+    transcript: &Transcript,
+    todos: &[Todo],
+    cached_goal: Option<&str>,
+) -> Option<SummaryResult> {
+    let goal = cached_goal
+        .map(str::to_string)
+        .or_else(|| transcript.first_prompt().and_then(goal_phrase));
+
+    let todo = todos
+        .iter()
+        .find(|t| t.status == "in_progress")
+        .map(|t| t.active_form.as_deref().unwrap_or(&t.content))
+        .and_then(|text| clip(text, ACTIVITY_WORDS));
+    let tool = transcript.last_tool().map(tool_phrase);
+    let sentence = transcript.last_text().and_then(last_sentence);
+    let activity = if transcript.tool_is_latest() {
+        todo.or(tool).or(sentence)
+    } else {
+        todo.or(sentence).or(tool)
+    };
+
+    let summary = match (&goal, activity) {
+        (Some(goal), Some(activity)) => format!("{}: {}", goal, activity),
+        (Some(goal), None) => goal.clone(),
+        (None, Some(activity)) => activity,
+        (None, None) => return None,
+    };
+    Some(SummaryResult {
+        // A guessed goal isn't cached; a model can do better later
+        goal: None,
+        summary,
+    })
+}
+
+/// The first sentence of a prompt, cut to a few words, without the colon
+/// that separates goal from activity.
+fn goal_phrase(prompt: &str) -> Option<String> {
+    let line = prompt.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let sentence = first_sentence(line);
+    let sentence = sentence.split(':').next().unwrap_or(sentence);
+    let goal = clip(sentence, GOAL_WORDS)?;
+    let mut chars = goal.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+/// The last sentence of prose in an assistant message, skipping code.
+fn last_sentence(text: &str) -> Option<String> {
+    let mut in_code = false;
+    let mut last = None;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if !in_code && !line.is_empty() {
+            last = Some(line);
+        }
+    }
+    let line = last?.trim_start_matches(['-', '*', '#', ' ']);
+    let sentence = line
+        .split_inclusive(['.', '!', '?'])
+        .map(str::trim)
+        .rev()
+        .find(|s| s.chars().any(char::is_alphanumeric))?;
+    clip(&sentence.replace(['`', '*'], ""), ACTIVITY_WORDS)
+}
+
+fn first_sentence(text: &str) -> &str {
+    text.split_inclusive(['.', '!', '?'])
+        .next()
+        .unwrap_or(text)
+        .trim()
+}
+
+/// Describes a tool call: "Editing main.rs", "Running cargo test".
+fn tool_phrase(tool: &ToolUse) -> String {
+    let target = tool.target.as_deref().unwrap_or("");
+    let file = target.rsplit('/').next().unwrap_or(target);
+    let phrase = match (tool.name.as_str(), target.is_empty()) {
+        ("Edit" | "MultiEdit" | "Write" | "NotebookEdit", false) => format!("Editing {}", file),
+        ("Edit" | "MultiEdit" | "Write" | "NotebookEdit", true) => "Editing files".to_string(),
+        ("Read", false) => format!("Reading {}", file),
+        ("Read", true) => "Reading files".to_string(),
+        ("Bash", false) => format!(
+            "Running {}",
+            target
+                .split_whitespace()
+                .take(3)
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        ("Grep" | "Glob", false) => format!("Searching for {}", target),
+        ("Grep" | "Glob", true) => "Searching the code".to_string(),
+        ("WebFetch" | "WebSearch", _) => "Researching online".to_string(),
+        ("Task", _) => "Running a subagent".to_string(),
+        (name, _) => format!("Using {}", name),
+    };
+    clip(&phrase, ACTIVITY_WORDS).unwrap_or(phrase)
+}
+
+/// Keeps the first `words` words, dropping trailing punctuation.
+fn clip(text: &str, words: usize) -> Option<String> {
+    let clipped = text
+        .split_whitespace()
+        .take(words)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let clipped = clipped.trim_end_matches(|c: char| !c.is_alphanumeric() && c != ')');
+    (!clipped.is_empty()).then(|| clipped.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn transcript(lines: &[&str]) -> Transcript {
+        let path = std::env::temp_dir().join(format!(
+            "sp-heuristic-{}-{}.jsonl",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let mut f = std::fs::File::create(&path).unwrap();
+        for line in lines {
+            writeln!(f, "{}", line).unwrap();
+        }
+        let transcript = Transcript::load(path.to_str().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        transcript
+    }
+
+    const PROMPT: &str = r#"{"type":"user","message":{"content":"fix the login redirect after the OAuth callback. It loops forever"}}"#;
+    const TEXT: &str = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Found it.\n```rust\nredirect();\n```\nThe callback drops the **state** param. Let me patch the handler."}]}}"#;
+    const EDIT: &str = r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/work/api/src/auth.rs"}}]}}"#;
+
+    fn todo(status: &str, active_form: &str) -> Todo {
+        Todo {
+            content: "Patch the handler".to_string(),
+            status: status.to_string(),
+            active_form: Some(active_form.to_string()),
+        }
+    }
+
+    #[test]
+    fn prefers_the_in_progress_todo() {
+        let t = transcript(&[PROMPT, TEXT, EDIT]);
+        let todos = [
+            todo("completed", "Reading the callback"),
+            todo("in_progress", "Patching the handler"),
+        ];
+        let result = summarize(&t, &todos, None).unwrap();
+        assert_eq!(
+            result.summary,
+            "Fix the login redirect after: Patching the handler"
+        );
+        assert_eq!(result.goal, None);
+    }
+
+    #[test]
+    fn uses_whichever_of_tool_and_text_came_last() {
+        let t = transcript(&[PROMPT, TEXT, EDIT]);
+        assert_eq!(
+            summarize(&t, &[], Some("OAuth loop")).unwrap().summary,
+            "OAuth loop: Editing auth.rs"
+        );
+
+        let t = transcript(&[PROMPT, EDIT, TEXT]);
+        assert_eq!(
+            summarize(&t, &[], Some("OAuth loop")).unwrap().summary,
+            "OAuth loop: Let me patch the handler"
+        );
+    }
+
+    #[test]
+    fn works_with_only_a_prompt_or_nothing() {
+        let t = transcript(&[r#"{"type":"user","message":{"content":"refactor: split db.rs"}}"#]);
+        assert_eq!(summarize(&t, &[], None).unwrap().summary, "Refactor");
+        assert!(summarize(&transcript(&[]), &[], None).is_none());
+    }
+
+    #[test]
+    fn describes_tools() {
+        let bash = ToolUse {
+            name: "Bash".to_string(),
+            target: Some("cargo test --workspace -- --nocapture".to_string()),
+        };
+        assert_eq!(tool_phrase(&bash), "Running cargo test --workspace");
+        let grep = ToolUse {
+            name: "Grep".to_string(),
+            target: None,
+        };
+        assert_eq!(tool_phrase(&grep), "Searching the code");
+    }
+}
@@ -0,0 +1,165 @@
+mod backend;
+pub mod health;
+pub mod heuristic;
+pub mod queue;
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use super::metrics::METRICS;
+use super::transcript::{prompt_text, Message, Transcript};
+pub use backend::{from_config, verify_anthropic_key, Failure, FailureKind, Summarizer};
+
+pub fn get_api_key() -> Option<String> {
+    if let Ok(key) = std::env::var("ANTHROPIC_API_KEY") {
+        return Some(key);
+    }
+
+    if let Some(auth) = crate::config::load_auth_config() {
+        return Some(auth.anthropic_api_key);
+    }
+
+    None
+}
+
+pub struct SummaryResult {
+    pub goal: Option<String>,
+    pub summary: String,
+}
+
+/// The summarizer selected under `[summarizer]` in the config, if it is
+/// usable.
+pub fn configured() -> Option<Box<dyn Summarizer>> {
+    backend::from_config(&crate::config::load_config().summarizer)
+}
+
+fn call_api(summarizer: &dyn Summarizer, prompt: &str, max_tokens: u32) -> Result<String, Failure> {
+    let started = std::time::Instant::now();
+    let result = summarizer.complete(prompt, max_tokens);
+    METRICS.record_summarizer_call(started.elapsed(), result.is_ok());
+    result
+}
+
+pub fn extract_user_prompts(transcript_path: &str) -> Vec<String> {
+    let Ok(file) = File::open(transcript_path) else {
+        return Vec::new();
+    };
+
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok())
+        .filter(|entry| entry.get("type").and_then(|v| v.as_str()) == Some("user"))
+        .filter_map(|entry| prompt_text(entry.get("message")?.get("content")?))
+        .collect()
+}
+
+fn join<'a>(messages: impl IntoIterator<Item = &'a Message>) -> String {
+    messages
+        .into_iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn summarize_session(
+    summarizer: &dyn Summarizer,
+    transcript: &Transcript,
+    cached_goal: Option<&str>,
+) -> Result<Option<SummaryResult>, Failure> {
+    let total = transcript.total_messages();
+    if total == 0 {
+        return Ok(None);
+    }
+
+    // Short sessions (< 5 messages): simple summary, no goal caching
+    if total < 5 {
+        let context = join(transcript.last_messages(total));
+        let prompt = format!(
+            "What is this conversation about? Reply with ONLY a short phrase (3-8 words).\n\n{}",
+            context
+        );
+        let summary = call_api(summarizer, &prompt, 30)?;
+        return Ok(Some(SummaryResult {
+            goal: None,
+            summary,
+        }));
+    }
+
+    // Build context: first 5 messages + last 10 messages
+    let context = if total <= 15 {
+        join(transcript.last_messages(total))
+    } else {
+        format!(
+            "{}\n...\n{}",
+            join(transcript.first_messages()),
+            join(transcript.last_messages(10))
+        )
+    };
+
+    // If we have a cached goal, only ask for current status
+    if let Some(goal) = cached_goal {
+        let last_context = join(transcript.last_messages(5));
+        let prompt = format!(
+            "Conversation excerpt:\n---\n{}\n---\n\n\
+             The overall task is \"{}\". Based on the last Assistant message, what is the current activity?\n\
+             Reply with ONLY a brief phrase (3-8 words).",
+            last_context, goal
+        );
+        let status = call_api(summarizer, &prompt, 40)?;
+        return Ok(Some(SummaryResult {
+            goal: None, // Don't update goal
+            summary: format!("{}: {}", goal, status),
+        }));
+    }
+
+    // First time: extract both goal and status
+    let prompt = format!(
+        "Conversation excerpt:\n---\n{}\n---\n\n\
+         Summarize as: Goal: current activity\n\
+         - Goal = overall task (2-4 words)\n\
+         - Current activity = from the LAST assistant message\n\
+         Example: Auth system: Running login tests\n\
+         Reply with ONLY that one line.",
+        context
+    );
+
+    let summary = call_api(summarizer, &prompt, 60)?;
+
+    // Extract goal from the summary (everything before the first colon)
+    // If no colon, use the whole summary as the goal to prevent re-extraction loops
+    let goal = summary
+        .split_once(':')
+        .map(|(g, _)| g.trim().to_string())
+        .unwrap_or_else(|| summary.clone());
+
+    Ok(Some(SummaryResult {
+        goal: Some(goal),
+        summary,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn extracts_user_prompts_skipping_tool_results_and_commands() {
+        let path = std::env::temp_dir().join(format!("sp-prompts-{}.jsonl", std::process::id()));
+        let mut f = File::create(&path).unwrap();
+        for line in [
+            r#"{"type":"user","message":{"role":"user","content":"fix the auth migration"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"On it"}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","content":"ok"}]}}"#,
+            r#"{"type":"user","message":{"content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"text","text":"now add tests"}]}}"#,
+        ] {
+            writeln!(f, "{}", line).unwrap();
+        }
+
+        let prompts = extract_user_prompts(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(prompts, vec!["fix the auth migration", "now add tests"]);
+    }
+}
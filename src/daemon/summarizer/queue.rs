@@ -0,0 +1,576 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+use super::Summarizer;
+use crate::config::SummarizerConfig;
+use crate::daemon::state::{AppState, WsMessage};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+/// A plate that never goes quiet is still summarized after this many
+/// debounce periods.
+const MAX_WAIT_DEBOUNCES: u32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryJob {
+    pub session_id: String,
+    pub transcript_path: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub debounce: Duration,
+    pub max_concurrent: usize,
+    /// 0 means no limit.
+    pub max_per_minute: u32,
+    /// 0 means no limit.
+    pub daily_budget: u32,
+}
+
+impl From<&SummarizerConfig> for Limits {
+    fn from(config: &SummarizerConfig) -> Self {
+        Self {
+            debounce: Duration::from_secs(config.debounce_secs),
+            max_concurrent: config.max_concurrent.max(1),
+            max_per_minute: config.max_per_minute,
+            daily_budget: config.daily_budget,
+        }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::from(&SummarizerConfig::default())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Next {
+    Run(SummaryJob),
+    /// Run `job` without the model, because there is no working model or
+    /// the daily budget or the rate limit is used up.
+    Heuristic(SummaryJob),
+    /// Nothing can start before this instant.
+    Wait(Instant),
+    /// Nothing to do until a job is queued or one finishes.
+    Idle,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueueStats {
+    pub pending: usize,
+    pub running: usize,
+    /// Queued jobs replaced by a newer request for the same plate.
+    pub superseded: u64,
+    pub calls_today: u32,
+    pub daily_budget: u32,
+    /// Jobs summarized without the model because the daily budget was
+    /// spent.
+    pub over_budget: u64,
+    /// Jobs summarized without the model because of `max_per_minute`.
+    pub rate_limited: u64,
+}
+
+struct Pending {
+    job: SummaryJob,
+    first_queued: Instant,
+    due: Instant,
+}
+
+/// Decides which summary jobs run when. Holds at most one queued job per
+/// plate and never runs two jobs for the same plate at once.
+pub struct Scheduler {
+    limits: Limits,
+    pending: HashMap<String, Pending>,
+    running: HashSet<String>,
+    started: VecDeque<Instant>,
+    day: Option<NaiveDate>,
+    calls_today: u32,
+    superseded: u64,
+    over_budget: u64,
+    rate_limited: u64,
+}
+
+impl Scheduler {
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            pending: HashMap::new(),
+            running: HashSet::new(),
+            started: VecDeque::new(),
+            day: None,
+            calls_today: 0,
+            superseded: 0,
+            over_budget: 0,
+            rate_limited: 0,
+        }
+    }
+
+    /// Queues `job`, replacing any job still waiting for the same plate and
+    /// pushing its start back by the debounce period.
+    pub fn push(&mut self, job: SummaryJob, now: Instant) {
+        let debounce = self.limits.debounce;
+        match self.pending.get_mut(&job.session_id) {
+            Some(pending) => {
+                let max_wait = pending.first_queued + debounce * MAX_WAIT_DEBOUNCES;
+                pending.job = job;
+                pending.due = (now + debounce).min(max_wait);
+                self.superseded += 1;
+            }
+            None => {
+                self.pending.insert(
+                    job.session_id.clone(),
+                    Pending {
+                        job,
+                        first_queued: now,
+                        due: now + debounce,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Picks the next job to start. Only jobs returned as `Run` count
+    /// toward the limits, so `model_ready` says whether one can be.
+    pub fn next(&mut self, now: Instant, today: NaiveDate, model_ready: bool) -> Next {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.calls_today = 0;
+        }
+        if self.running.len() >= self.limits.max_concurrent {
+            return Next::Idle;
+        }
+
+        while self
+            .started
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            self.started.pop_front();
+        }
+
+        let Some(ready) = self
+            .pending
+            .values()
+            .filter(|p| !self.running.contains(&p.job.session_id))
+            .min_by_key(|p| p.due)
+        else {
+            return Next::Idle;
+        };
+
+        if ready.due > now {
+            return Next::Wait(ready.due);
+        }
+
+        let session_id = ready.job.session_id.clone();
+        let Some(pending) = self.pending.remove(&session_id) else {
+            return Next::Idle;
+        };
+        self.running.insert(session_id);
+
+        if !model_ready {
+            return Next::Heuristic(pending.job);
+        }
+        // The limits are on model calls; past them, plates still get the
+        // heuristic summary
+        let budget = self.limits.daily_budget;
+        if budget > 0 && self.calls_today >= budget {
+            self.over_budget += 1;
+            return Next::Heuristic(pending.job);
+        }
+        let per_minute = self.limits.max_per_minute as usize;
+        if per_minute > 0 && self.started.len() >= per_minute {
+            self.rate_limited += 1;
+            return Next::Heuristic(pending.job);
+        }
+        self.started.push_back(now);
+        self.calls_today += 1;
+        Next::Run(pending.job)
+    }
+
+    pub fn finished(&mut self, session_id: &str) {
+        self.running.remove(session_id);
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        QueueStats {
+            pending: self.pending.len(),
+            running: self.running.len(),
+            superseded: self.superseded,
+            calls_today: self.calls_today,
+            daily_budget: self.limits.daily_budget,
+            over_budget: self.over_budget,
+            rate_limited: self.rate_limited,
+        }
+    }
+}
+
+pub struct SummaryQueue {
+    scheduler: Mutex<Scheduler>,
+    wake: Notify,
+}
+
+impl Default for SummaryQueue {
+    fn default() -> Self {
+        Self {
+            scheduler: Mutex::new(Scheduler::new(Limits::default())),
+            wake: Notify::new(),
+        }
+    }
+}
+
+impl SummaryQueue {
+    pub fn set_limits(&self, limits: Limits) {
+        self.scheduler.lock().unwrap().limits = limits;
+        self.wake.notify_one();
+    }
+
+    pub fn enqueue(&self, job: SummaryJob) {
+        self.scheduler.lock().unwrap().push(job, Instant::now());
+        self.wake.notify_one();
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        self.scheduler.lock().unwrap().stats()
+    }
+
+    fn next(&self, model_ready: bool) -> Next {
+        let today = chrono::Utc::now().date_naive();
+        self.scheduler
+            .lock()
+            .unwrap()
+            .next(Instant::now(), today, model_ready)
+    }
+
+    fn finished(&self, session_id: &str) {
+        self.scheduler.lock().unwrap().finished(session_id);
+        self.wake.notify_one();
+    }
+}
+
+/// The configured summarizer, unless it keeps failing and is backing off.
+fn available_model(state: &AppState) -> Option<Box<dyn Summarizer>> {
+    let now = chrono::Utc::now().to_rfc3339();
+    if state.summarizer_failures.backing_off(&now) {
+        return None;
+    }
+    super::configured()
+}
+
+/// Summarizes a plate with `model`, or without one when it is `None` or
+/// fails.
+fn run_job(state: &AppState, job: &SummaryJob, model: Option<&dyn Summarizer>) {
+    // Only a goal a model extracted is cached; the summary may start with
+    // one the heuristic guessed
+    let (cached_goal, todos) = {
+        let db = state.db.lock().unwrap();
+        (
+            db.get_goal(&job.session_id).ok().flatten(),
+            db.get_todos(&job.session_id).unwrap_or_default(),
+        )
+    };
+
+    // The summary describes the transcript as read now
+    let as_of = chrono::Utc::now().to_rfc3339();
+    let Some(transcript) =
+        state
+            .transcripts
+            .read(&job.session_id, &job.transcript_path, Clone::clone)
+    else {
+        return;
+    };
+    // Without a working model, fall back to what the transcript says
+    let failures = &state.summarizer_failures;
+    let generated = model.and_then(|s| {
+        match super::summarize_session(s, &transcript, cached_goal.as_deref()) {
+            Ok(result) => {
+                failures.record_success(&chrono::Utc::now().to_rfc3339());
+                result
+            }
+            Err(failure) => {
+                failures.record_failure(&failure, chrono::Utc::now());
+                None
+            }
+        }
+    });
+    let Some(result) = generated
+        .or_else(|| super::heuristic::summarize(&transcript, &todos, cached_goal.as_deref()))
+    else {
+        return;
+    };
+
+    let db = state.db.lock().unwrap();
+    if !db
+        .set_summary_if_newer(&job.session_id, &result.summary, &as_of)
+        .unwrap_or(false)
+    {
+        return;
+    }
+    if let Some(goal) = result.goal {
+        let _ = db.set_goal(&job.session_id, &goal);
+    }
+    let _ = state
+        .tx
+        .send(WsMessage::PlateUpdate(job.session_id.clone()));
+}
+
+/// Runs queued summary jobs as the scheduler allows.
+pub fn spawn_summary_worker(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        loop {
+            let model = available_model(&state);
+            let (job, model) = match state.summaries.next(model.is_some()) {
+                Next::Run(job) => (job, model),
+                Next::Heuristic(job) => (job, None),
+                Next::Wait(at) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(at.into()) => {}
+                        _ = state.summaries.wake.notified() => {}
+                    }
+                    continue;
+                }
+                Next::Idle => {
+                    state.summaries.wake.notified().await;
+                    continue;
+                }
+            };
+            let state = state.clone();
+            tokio::spawn(async move {
+                let worker_state = state.clone();
+                let session_id = job.session_id.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    run_job(&worker_state, &job, model.as_deref())
+                })
+                .await;
+                state.summaries.finished(&session_id);
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::summarizer::Failure;
+
+    fn job(session_id: &str, transcript: &str) -> SummaryJob {
+        SummaryJob {
+            session_id: session_id.to_string(),
+            transcript_path: transcript.to_string(),
+        }
+    }
+
+    fn limits() -> Limits {
+        Limits {
+            debounce: Duration::from_secs(3),
+            max_concurrent: 2,
+            max_per_minute: 0,
+            daily_budget: 0,
+        }
+    }
+
+    fn day(n: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, n).unwrap()
+    }
+
+    #[test]
+    fn debounces_and_keeps_only_the_latest_job() {
+        let mut scheduler = Scheduler::new(limits());
+        let t0 = Instant::now();
+        scheduler.push(job("a", "old"), t0);
+        scheduler.push(job("a", "new"), t0 + Duration::from_secs(2));
+
+        let due = t0 + Duration::from_secs(5);
+        assert_eq!(
+            scheduler.next(t0 + Duration::from_secs(3), day(1), true),
+            Next::Wait(due)
+        );
+        assert_eq!(
+            scheduler.next(due, day(1), true),
+            Next::Run(job("a", "new"))
+        );
+        assert_eq!(scheduler.next(due, day(1), true), Next::Idle);
+        assert_eq!(scheduler.stats().superseded, 1);
+    }
+
+    #[test]
+    fn busy_plates_are_not_postponed_forever() {
+        let mut scheduler = Scheduler::new(limits());
+        let t0 = Instant::now();
+        for secs in 0..30 {
+            scheduler.push(job("a", "t"), t0 + Duration::from_secs(secs));
+        }
+        let max_wait = t0 + Duration::from_secs(15);
+        assert_eq!(
+            scheduler.next(max_wait, day(1), true),
+            Next::Run(job("a", "t"))
+        );
+    }
+
+    #[test]
+    fn limits_concurrency() {
+        let mut scheduler = Scheduler::new(limits());
+        let t0 = Instant::now();
+        let later = t0 + Duration::from_secs(10);
+        for id in ["a", "b", "c"] {
+            scheduler.push(job(id, "1"), t0);
+        }
+        let Next::Run(first) = scheduler.next(later, day(1), true) else {
+            panic!("expected a job to start");
+        };
+        assert!(matches!(scheduler.next(later, day(1), true), Next::Run(_)));
+        assert_eq!(scheduler.next(later, day(1), true), Next::Idle);
+
+        scheduler.finished(&first.session_id);
+        assert!(matches!(scheduler.next(later, day(1), true), Next::Run(_)));
+        assert_eq!(scheduler.stats().running, 2);
+    }
+
+    #[test]
+    fn running_plate_is_not_started_twice() {
+        let mut scheduler = Scheduler::new(limits());
+        let t0 = Instant::now();
+        let later = t0 + Duration::from_secs(10);
+        scheduler.push(job("a", "1"), t0);
+        assert_eq!(
+            scheduler.next(later, day(1), true),
+            Next::Run(job("a", "1"))
+        );
+        scheduler.push(job("a", "2"), later);
+        assert_eq!(
+            scheduler.next(later + Duration::from_secs(10), day(1), true),
+            Next::Idle
+        );
+        scheduler.finished("a");
+        assert_eq!(
+            scheduler.next(later + Duration::from_secs(10), day(1), true),
+            Next::Run(job("a", "2"))
+        );
+    }
+
+    #[test]
+    fn rate_limited_jobs_run_without_the_model() {
+        let mut scheduler = Scheduler::new(Limits {
+            max_per_minute: 1,
+            ..limits()
+        });
+        let t0 = Instant::now();
+        scheduler.push(job("a", "1"), t0);
+        scheduler.push(job("b", "1"), t0);
+        let ready = t0 + Duration::from_secs(3);
+        assert!(matches!(scheduler.next(ready, day(1), true), Next::Run(_)));
+        assert!(matches!(
+            scheduler.next(ready, day(1), true),
+            Next::Heuristic(_)
+        ));
+        assert_eq!(scheduler.stats().rate_limited, 1);
+
+        scheduler.finished("a");
+        scheduler.finished("b");
+        scheduler.push(job("a", "2"), ready);
+        assert_eq!(
+            scheduler.next(ready + RATE_WINDOW, day(1), true),
+            Next::Run(job("a", "2"))
+        );
+    }
+
+    #[test]
+    fn over_budget_jobs_run_without_the_model_until_the_next_day() {
+        let mut scheduler = Scheduler::new(Limits {
+            daily_budget: 1,
+            ..limits()
+        });
+        let t0 = Instant::now();
+        let later = t0 + Duration::from_secs(10);
+        scheduler.push(job("a", "1"), t0);
+        scheduler.push(job("b", "1"), t0);
+        let Next::Run(ran) = scheduler.next(later, day(1), true) else {
+            panic!("expected a job to start");
+        };
+        scheduler.finished(&ran.session_id);
+        let Next::Heuristic(fallback) = scheduler.next(later, day(1), true) else {
+            panic!("expected the other job to run without the model");
+        };
+        assert_ne!(fallback.session_id, ran.session_id);
+        scheduler.finished(&fallback.session_id);
+        assert_eq!(scheduler.stats().over_budget, 1);
+        assert_eq!(scheduler.stats().pending, 0);
+
+        scheduler.push(job("b", "2"), later);
+        let tomorrow = later + Duration::from_secs(10);
+        assert_eq!(
+            scheduler.next(tomorrow, day(2), true),
+            Next::Run(job("b", "2"))
+        );
+        assert_eq!(scheduler.stats().calls_today, 1);
+    }
+
+    #[test]
+    fn jobs_without_a_model_run_heuristically_without_counting() {
+        let mut scheduler = Scheduler::new(Limits {
+            daily_budget: 1,
+            ..limits()
+        });
+        let t0 = Instant::now();
+        let later = t0 + Duration::from_secs(10);
+        scheduler.push(job("a", "1"), t0);
+        assert_eq!(
+            scheduler.next(later, day(1), false),
+            Next::Heuristic(job("a", "1"))
+        );
+        scheduler.finished("a");
+        assert_eq!(scheduler.stats().calls_today, 0);
+
+        scheduler.push(job("a", "2"), later);
+        let due = later + Duration::from_secs(3);
+        assert_eq!(scheduler.next(due, day(1), true), Next::Run(job("a", "2")));
+        assert_eq!(scheduler.stats().calls_today, 1);
+    }
+
+    /// Replies with a fixed line and remembers the prompts it was sent.
+    struct FakeModel(Mutex<Vec<String>>);
+
+    impl Summarizer for FakeModel {
+        fn complete(&self, prompt: &str, _max_tokens: u32) -> Result<String, Failure> {
+            self.0.lock().unwrap().push(prompt.to_string());
+            Ok("Login redirect: Patching the callback".to_string())
+        }
+    }
+
+    #[test]
+    fn model_extracts_its_own_goal_after_a_heuristic_summary() {
+        let file = crate::test_support::TempFile::new("summary");
+        for _ in 0..3 {
+            file.append_lines(&[
+                r#"{"type":"user","message":{"content":"fix the login redirect after the OAuth callback"}}"#,
+                r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Let me patch the handler."}]}}"#,
+            ]);
+        }
+        let db = crate::db::Database::open(std::path::Path::new(":memory:")).unwrap();
+        let session_id = db
+            .register_placeholder("/work/api", "2026-01-01T00:00:00+00:00")
+            .unwrap();
+        let state = AppState::new(db);
+        let job = job(&session_id, file.path());
+
+        run_job(&state, &job, None);
+        {
+            let db = state.db.lock().unwrap();
+            let summary = db.get_summary(&session_id).unwrap().unwrap();
+            assert!(summary.starts_with("Fix the login redirect"), "{}", summary);
+            assert_eq!(db.get_goal(&session_id).unwrap(), None);
+        }
+
+        let model = FakeModel(Mutex::new(Vec::new()));
+        run_job(&state, &job, Some(&model));
+        let prompts = model.0.lock().unwrap();
+        assert!(prompts[0].contains("Summarize as: Goal: current activity"));
+        assert_eq!(
+            state.db.lock().unwrap().get_goal(&session_id).unwrap(),
+            Some("Login redirect".to_string())
+        );
+    }
+}
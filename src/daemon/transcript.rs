@@ -0,0 +1,596 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Mutex;
+
+use crate::config::Prices;
+use crate::models::{TokenUsage, UsageTotals};
+
+/// Messages kept from the start of a transcript, for the summarizer's goal.
+const HEAD_MESSAGES: usize = 5;
+/// Most recent messages kept per transcript.
+const RECENT_MESSAGES: usize = 50;
+/// Characters kept from each message.
+const MESSAGE_CHARS: usize = 200;
+
+/// A condensed transcript message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    User(String),
+    Assistant(String),
+    Tool(String),
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Message::User(text) => write!(f, "User: {}", text),
+            Message::Assistant(text) => write!(f, "Assistant: {}", text),
+            Message::Tool(name) => write!(f, "Tool: {}", name),
+        }
+    }
+}
+
+/// A tool the assistant called, with the file, command or pattern it was
+/// given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolUse {
+    pub name: String,
+    pub target: Option<String>,
+}
+
+/// Tokens one model used on one UTC day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageRecord {
+    pub model: String,
+    pub day: String,
+    pub tokens: TokenUsage,
+}
+
+impl UsageRecord {
+    pub fn totals(&self, prices: &Prices) -> UsageTotals {
+        UsageTotals {
+            tokens: self.tokens,
+            cost_usd: prices.cost(&self.model, &self.tokens),
+        }
+    }
+}
+
+/// Where reading a JSONL file left off.
+#[derive(Debug, Clone, Default)]
+pub struct Tail {
+    path: String,
+    /// Bytes consumed; a trailing line without a newline is left unread
+    /// until it is complete.
+    offset: u64,
+}
+
+/// Lines read by [`Tail::read`].
+pub struct Appended {
+    /// The path changed or the file shrank, so reading started over from
+    /// the top; whatever was built from earlier lines is stale.
+    pub restarted: bool,
+    /// Each complete line, `None` for one that isn't valid JSON.
+    pub entries: Vec<Option<serde_json::Value>>,
+}
+
+impl Tail {
+    /// Reads the lines appended to `path` since the last call.
+    pub fn read(&mut self, path: &str) -> std::io::Result<Appended> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let restarted = self.path != path || len < self.offset;
+        if restarted {
+            self.path = path.to_string();
+            self.offset = 0;
+        }
+        let mut entries = Vec::new();
+        if len == self.offset {
+            return Ok(Appended { restarted, entries });
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut appended = Vec::new();
+        file.take(len - self.offset).read_to_end(&mut appended)?;
+
+        let mut consumed = 0;
+        for line in appended.split_inclusive(|&b| b == b'\n') {
+            let complete = line.ends_with(b"\n");
+            let entry = serde_json::from_slice::<serde_json::Value>(line);
+            // A final line without a newline may still be being written;
+            // take it only if it already parses.
+            if !complete && entry.is_err() {
+                break;
+            }
+            consumed += line.len();
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            entries.push(entry.ok());
+        }
+        self.offset += consumed as u64;
+        Ok(Appended { restarted, entries })
+    }
+}
+
+/// What has been read of one transcript file so far.
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    tail: Tail,
+    head: Vec<Message>,
+    recent: VecDeque<Message>,
+    total: usize,
+    completed: bool,
+    first_prompt: Option<String>,
+    last_text: Option<String>,
+    last_tool: Option<ToolUse>,
+    /// Whether the tool call came after the last assistant text.
+    tool_is_latest: bool,
+    /// Usage since the start of the file, by model and day.
+    usage: Vec<UsageRecord>,
+    /// Whether `usage` grew since the last `usage_update`.
+    usage_changed: bool,
+    /// A reply with several content blocks is written as several entries
+    /// repeating the same usage; only the first is counted.
+    last_message_id: Option<String>,
+}
+
+impl Transcript {
+    /// Reads a whole transcript once, without caching.
+    pub fn load(path: &str) -> Option<Self> {
+        let mut transcript = Self::default();
+        transcript.catch_up(path).ok()?;
+        Some(transcript)
+    }
+
+    /// Consumes whatever was appended since the last call. Starts over when
+    /// the path changes or the file shrank.
+    pub fn catch_up(&mut self, path: &str) -> std::io::Result<()> {
+        let appended = self.tail.read(path)?;
+        if appended.restarted {
+            *self = Self {
+                tail: std::mem::take(&mut self.tail),
+                ..Default::default()
+            };
+        }
+        for entry in appended.entries {
+            match entry {
+                Some(entry) => self.push_entry(&entry),
+                None => self.completed = false,
+            }
+        }
+        Ok(())
+    }
+
+    fn push_entry(&mut self, entry: &serde_json::Value) {
+        let entry_type = entry.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        let msg = entry.get("message").unwrap_or(&serde_json::Value::Null);
+        self.completed = match entry_type {
+            "summary" => true,
+            "assistant" => msg.get("stop_reason").and_then(|v| v.as_str()) == Some("end_turn"),
+            _ => false,
+        };
+
+        let content = msg.get("content");
+        if entry_type == "assistant" {
+            self.note_activity(content);
+            self.note_usage(entry, msg);
+        }
+        match entry_type {
+            "user" => {
+                if self.first_prompt.is_none() {
+                    self.first_prompt = content.and_then(prompt_text);
+                }
+                if let Some(text) = content.and_then(|c| c.as_str()).and_then(condense) {
+                    self.push(Message::User(text));
+                }
+            }
+            "assistant" => {
+                if let Some(arr) = content.and_then(|c| c.as_array()) {
+                    for block in arr.iter().take(3) {
+                        match block.get("type").and_then(|v| v.as_str()) {
+                            Some("text") => {
+                                if let Some(text) = block
+                                    .get("text")
+                                    .and_then(|v| v.as_str())
+                                    .and_then(condense)
+                                {
+                                    self.push(Message::Assistant(text));
+                                }
+                            }
+                            Some("tool_use") => {
+                                let name = block
+                                    .get("name")
+                                    .and_then(|v| v.as_str())
+                                    .unwrap_or("unknown");
+                                self.push(Message::Tool(name.to_string()));
+                            }
+                            _ => {}
+                        }
+                    }
+                } else if let Some(text) = content.and_then(|c| c.as_str()).and_then(condense) {
+                    self.push(Message::Assistant(text));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Remembers the last text and tool call, over every block rather than
+    /// the few kept as messages.
+    fn note_activity(&mut self, content: Option<&serde_json::Value>) {
+        if let Some(text) = content.and_then(|c| c.as_str()) {
+            if !text.trim().is_empty() {
+                self.last_text = Some(text.trim().to_string());
+                self.tool_is_latest = false;
+            }
+            return;
+        }
+        for block in content.and_then(|c| c.as_array()).into_iter().flatten() {
+            match block.get("type").and_then(|v| v.as_str()) {
+                Some("text") => {
+                    let text = block.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                    if !text.trim().is_empty() {
+                        self.last_text = Some(text.trim().to_string());
+                        self.tool_is_latest = false;
+                    }
+                }
+                Some("tool_use") => {
+                    let target = block.get("input").and_then(tool_target);
+                    self.last_tool = Some(ToolUse {
+                        name: block
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown")
+                            .to_string(),
+                        target,
+                    });
+                    self.tool_is_latest = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn note_usage(&mut self, entry: &serde_json::Value, msg: &serde_json::Value) {
+        let Some(usage) = msg.get("usage") else {
+            return;
+        };
+        let id = msg.get("id").and_then(|v| v.as_str());
+        if id.is_some() && id == self.last_message_id.as_deref() {
+            return;
+        }
+        self.last_message_id = id.map(str::to_string);
+
+        let count = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        let tokens = TokenUsage {
+            input_tokens: count("input_tokens"),
+            output_tokens: count("output_tokens"),
+            cache_creation_tokens: count("cache_creation_input_tokens"),
+            cache_read_tokens: count("cache_read_input_tokens"),
+        };
+        if tokens.total() == 0 {
+            return;
+        }
+        let model = msg
+            .get("model")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let day = entry
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&chrono::Utc))
+            .unwrap_or_else(chrono::Utc::now)
+            .format("%Y-%m-%d")
+            .to_string();
+
+        match self
+            .usage
+            .iter_mut()
+            .find(|r| r.model == model && r.day == day)
+        {
+            Some(record) => record.tokens.add(&tokens),
+            None => self.usage.push(UsageRecord { model, day, tokens }),
+        }
+        self.usage_changed = true;
+    }
+
+    fn push(&mut self, message: Message) {
+        if self.head.len() < HEAD_MESSAGES {
+            self.head.push(message.clone());
+        }
+        if self.recent.len() == RECENT_MESSAGES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(message);
+        self.total += 1;
+    }
+
+    /// Number of messages read, including those no longer kept.
+    pub fn total_messages(&self) -> usize {
+        self.total
+    }
+
+    /// The first few messages of the session.
+    pub fn first_messages(&self) -> &[Message] {
+        &self.head
+    }
+
+    /// Up to `n` of the latest messages, oldest first.
+    pub fn last_messages(&self, n: usize) -> impl Iterator<Item = &Message> {
+        self.recent.iter().skip(self.recent.len().saturating_sub(n))
+    }
+
+    /// The first thing the user asked, in full.
+    pub fn first_prompt(&self) -> Option<&str> {
+        self.first_prompt.as_deref()
+    }
+
+    /// The latest assistant text, in full.
+    pub fn last_text(&self) -> Option<&str> {
+        self.last_text.as_deref()
+    }
+
+    /// The latest tool call.
+    pub fn last_tool(&self) -> Option<&ToolUse> {
+        self.last_tool.as_ref()
+    }
+
+    /// Whether the latest tool call came after the latest assistant text.
+    pub fn tool_is_latest(&self) -> bool {
+        self.tool_is_latest
+    }
+
+    /// Whether the last entry is a summary or an assistant turn that ended.
+    pub fn shows_completion(&self) -> bool {
+        self.completed
+    }
+
+    /// The session's whole usage by model and day, if it changed since the
+    /// last call; the file read from the start counts as a change.
+    pub fn usage_update(&mut self) -> Option<Vec<UsageRecord>> {
+        std::mem::take(&mut self.usage_changed).then(|| self.usage.clone())
+    }
+}
+
+/// The file, command, pattern or URL a tool call's input is about.
+pub fn tool_target(input: &serde_json::Value) -> Option<String> {
+    [
+        "file_path",
+        "notebook_path",
+        "path",
+        "command",
+        "pattern",
+        "url",
+    ]
+    .iter()
+    .find_map(|key| input.get(*key)?.as_str())
+    .map(str::to_string)
+}
+
+/// Truncates a message, skipping very short ones (likely just
+/// confirmations).
+fn condense(text: &str) -> Option<String> {
+    let truncated: String = text.chars().take(MESSAGE_CHARS).collect();
+    (truncated.len() >= 10).then_some(truncated)
+}
+
+/// The text of a user entry's content, unless it is a tool result or a
+/// tag-wrapped slash command or hook output.
+pub fn prompt_text(content: &serde_json::Value) -> Option<String> {
+    let text = match content.as_str() {
+        Some(text) => text.to_string(),
+        None => content
+            .as_array()?
+            .iter()
+            .filter(|block| block.get("type").and_then(|v| v.as_str()) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(|v| v.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    let text = text.trim();
+    if text.is_empty() || text.starts_with('<') {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+/// Transcripts being followed, by session id.
+#[derive(Default)]
+pub struct TranscriptCache {
+    transcripts: Mutex<HashMap<String, Transcript>>,
+}
+
+impl TranscriptCache {
+    /// Catches up on the session's transcript and passes it to `f`. `None`
+    /// when the file can't be read.
+    pub fn read<R>(
+        &self,
+        session_id: &str,
+        path: &str,
+        f: impl FnOnce(&Transcript) -> R,
+    ) -> Option<R> {
+        let mut transcripts = self.transcripts.lock().unwrap();
+        let transcript = transcripts.entry(session_id.to_string()).or_default();
+        if transcript.catch_up(path).is_err() {
+            transcripts.remove(session_id);
+            return None;
+        }
+        Some(f(transcript))
+    }
+
+    /// Catches up on the session's transcript and returns its whole usage
+    /// if that changed. `None` when the file can't be read.
+    pub fn usage_update(&self, session_id: &str, path: &str) -> Option<Vec<UsageRecord>> {
+        let mut transcripts = self.transcripts.lock().unwrap();
+        let transcript = transcripts.entry(session_id.to_string()).or_default();
+        if transcript.catch_up(path).is_err() {
+            transcripts.remove(session_id);
+            return None;
+        }
+        transcript.usage_update()
+    }
+
+    /// Drops transcripts of sessions that are gone.
+    pub fn retain(&self, live: &HashSet<String>) {
+        self.transcripts
+            .lock()
+            .unwrap()
+            .retain(|session_id, _| live.contains(session_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempFile;
+
+    const USER: &str = r#"{"type":"user","message":{"content":"fix the login redirect"}}"#;
+    const TOOL: &str = r#"{"type":"assistant","message":{"stop_reason":"tool_use","content":[{"type":"tool_use","name":"Edit"}]}}"#;
+    const DONE: &str = r#"{"type":"assistant","message":{"stop_reason":"end_turn","content":[{"type":"text","text":"The redirect is fixed."}]}}"#;
+
+    fn texts(transcript: &Transcript, n: usize) -> Vec<String> {
+        transcript
+            .last_messages(n)
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn reads_only_appended_lines() {
+        let file = TempFile::new("append");
+        let cache = TranscriptCache::default();
+        file.append(&format!("{}\n{}\n", USER, TOOL));
+        let total = cache.read("s", file.path(), |t| t.total_messages());
+        assert_eq!(total, Some(2));
+
+        file.append(&format!("{}\n", DONE));
+        cache
+            .read("s", file.path(), |t| {
+                assert_eq!(t.total_messages(), 3);
+                assert!(t.shows_completion());
+                assert_eq!(
+                    texts(t, 2),
+                    vec!["Tool: Edit", "Assistant: The redirect is fixed."]
+                );
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn waits_for_partial_lines() {
+        let file = TempFile::new("partial");
+        let cache = TranscriptCache::default();
+        file.append(&format!("{}\n{}", USER, &DONE[..20]));
+        let total = cache.read("s", file.path(), |t| t.total_messages());
+        assert_eq!(total, Some(1));
+
+        file.append(&format!("{}\n", &DONE[20..]));
+        let done = cache.read("s", file.path(), |t| {
+            (t.total_messages(), t.shows_completion())
+        });
+        assert_eq!(done, Some((2, true)));
+    }
+
+    #[test]
+    fn starts_over_when_the_file_shrinks() {
+        let file = TempFile::new("shrink");
+        let cache = TranscriptCache::default();
+        file.append(&format!("{}\n{}\n{}\n", USER, TOOL, DONE));
+        cache.read("s", file.path(), |_| ()).unwrap();
+
+        std::fs::write(&file.0, format!("{}\n", TOOL)).unwrap();
+        let read = cache.read("s", file.path(), |t| {
+            (t.total_messages(), t.shows_completion())
+        });
+        assert_eq!(read, Some((1, false)));
+    }
+
+    #[test]
+    fn keeps_the_first_and_latest_messages() {
+        let file = TempFile::new("ring");
+        for i in 0..RECENT_MESSAGES + 10 {
+            file.append(&format!(
+                "{{\"type\":\"user\",\"message\":{{\"content\":\"prompt number {}\"}}}}\n",
+                i
+            ));
+        }
+        let transcript = Transcript::load(file.path()).unwrap();
+        assert_eq!(transcript.total_messages(), RECENT_MESSAGES + 10);
+        assert_eq!(
+            transcript.first_messages()[0],
+            Message::User("prompt number 0".to_string())
+        );
+        assert_eq!(
+            transcript.last_messages(usize::MAX).count(),
+            RECENT_MESSAGES
+        );
+        assert_eq!(
+            texts(&transcript, 1),
+            vec![format!("User: prompt number {}", RECENT_MESSAGES + 9)]
+        );
+    }
+
+    #[test]
+    fn counts_usage_once_per_reply() {
+        let file = TempFile::new("usage");
+        let cache = TranscriptCache::default();
+        let reply = |id: &str, day: &str| {
+            format!(
+                r#"{{"type":"assistant","timestamp":"{}T10:00:00.000Z","message":{{"id":"{}","model":"claude-sonnet-4-5","usage":{{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100}},"content":[]}}}}"#,
+                day, id
+            )
+        };
+        // Two entries of the same reply, then another reply
+        file.append(&format!(
+            "{}\n{}\n{}\n",
+            reply("msg_1", "2026-01-01"),
+            reply("msg_1", "2026-01-01"),
+            reply("msg_2", "2026-01-01")
+        ));
+        let usage = cache.usage_update("s", file.path()).unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].model, "claude-sonnet-4-5");
+        assert_eq!(usage[0].day, "2026-01-01");
+        assert_eq!(usage[0].tokens.input_tokens, 20);
+        assert_eq!(usage[0].tokens.total(), 230);
+
+        assert!(cache.usage_update("s", file.path()).is_none());
+        file.append(&format!("{}\n", reply("msg_3", "2026-01-02")));
+        let usage = cache.usage_update("s", file.path()).unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[1].day, "2026-01-02");
+    }
+
+    #[test]
+    fn rereading_a_counted_transcript_keeps_the_totals() {
+        let file = TempFile::new("recount");
+        file.append(
+            r#"{"type":"assistant","timestamp":"2026-01-01T10:00:00.000Z","message":{"id":"msg_1","model":"claude-sonnet-4-5","usage":{"input_tokens":1000,"output_tokens":500},"content":[]}}
+"#,
+        );
+        let db = crate::db::Database::open(std::path::Path::new(":memory:")).unwrap();
+        db.register_placeholder("/work/api", "2026-01-01T00:00:00+00:00")
+            .unwrap();
+        let session_id = db.get_plates().unwrap()[0].session_id.clone();
+        let prices = Prices::default();
+        let store = |cache: &TranscriptCache| {
+            let usage = cache.usage_update(&session_id, file.path()).unwrap();
+            let rows: Vec<_> = usage
+                .iter()
+                .map(|r| (r.model.as_str(), r.day.as_str(), r.totals(&prices)))
+                .collect();
+            db.set_usage(&session_id, &rows).unwrap();
+            let plate = db.get_plate(&session_id).unwrap().unwrap();
+            (plate.tokens, plate.cost_usd)
+        };
+
+        let counted = store(&TranscriptCache::default());
+        assert_eq!(counted.0, 1500);
+        // As after a daemon restart: a new cache reads the file from the start
+        assert_eq!(store(&TranscriptCache::default()), counted);
+    }
+}
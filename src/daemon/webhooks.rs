@@ -0,0 +1,493 @@
+use ring::hmac;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::state::AppState;
+use crate::config::WebhookConfig;
+use crate::db::{StatusChange, WebhookDelivery};
+
+const POLL_INTERVAL_SECS: u64 = 5;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const BATCH_SIZE: usize = 50;
+pub const MAX_ATTEMPTS: u32 = 8;
+const BASE_BACKOFF_SECS: i64 = 5;
+const MAX_BACKOFF_SECS: i64 = 3600;
+
+pub const SIGNATURE_HEADER: &str = "X-Plate-Spinner-Signature";
+pub const DELIVERY_HEADER: &str = "X-Plate-Spinner-Delivery";
+
+fn matches(webhook: &WebhookConfig, change: &StatusChange) -> bool {
+    if !webhook.statuses.is_empty() && !webhook.statuses.contains(&change.transition.to_status) {
+        return false;
+    }
+    if !webhook.projects.is_empty() {
+        let Some(plate) = &change.plate else {
+            return false;
+        };
+        if !webhook.projects.iter().any(|p| plate.in_project(p)) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Delay before the next attempt after `attempts` failures: 5s, 10s, 20s...
+/// capped at an hour.
+fn backoff_secs(attempts: u32) -> i64 {
+    BASE_BACKOFF_SECS
+        .saturating_mul(1i64 << attempts.min(20))
+        .min(MAX_BACKOFF_SECS)
+}
+
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, body);
+    let hex: String = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+pub struct WebhookWorker {
+    state: Arc<AppState>,
+    webhooks: Vec<WebhookConfig>,
+    client: reqwest::Client,
+    last_transition_id: i64,
+}
+
+impl WebhookWorker {
+    /// Picks up where the last run left off. The first run starts from the
+    /// latest transition rather than replaying the whole history.
+    pub fn new(state: Arc<AppState>, webhooks: Vec<WebhookConfig>) -> Self {
+        let last_transition_id = {
+            let db = state.db.lock().unwrap();
+            match db.webhook_cursor() {
+                Ok(Some(id)) => id,
+                _ => {
+                    let latest = db.latest_transition_id().unwrap_or(0);
+                    let _ = db.enqueue_webhooks(latest, &[], &chrono::Utc::now().to_rfc3339());
+                    latest
+                }
+            }
+        };
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        Self {
+            state,
+            webhooks,
+            client,
+            last_transition_id,
+        }
+    }
+
+    /// Queues a delivery for every new transition that a webhook wants.
+    /// Stops at the first transition it can't read, to retry it next time.
+    /// Takes the database lock a batch at a time, so handlers get a turn
+    /// while a long backlog is queued.
+    pub fn enqueue_new(&mut self, now: &str) {
+        loop {
+            let db = self.state.db.lock().unwrap();
+            let Ok(transitions) = db.get_transitions_after(self.last_transition_id, BATCH_SIZE)
+            else {
+                return;
+            };
+            let read = transitions.len();
+            let mut last_id = self.last_transition_id;
+            let mut deliveries = Vec::new();
+            for transition in transitions {
+                let id = transition.id;
+                let Ok(change) = db.status_change(transition) else {
+                    break;
+                };
+                if let Ok(payload) = serde_json::to_string(&change) {
+                    for (idx, webhook) in self.webhooks.iter().enumerate() {
+                        if matches(webhook, &change) {
+                            deliveries.push((idx, webhook.url.as_str(), payload.clone()));
+                        }
+                    }
+                }
+                last_id = id;
+            }
+            if last_id == self.last_transition_id {
+                return;
+            }
+            let deliveries: Vec<(usize, &str, &str)> = deliveries
+                .iter()
+                .map(|(idx, url, payload)| (*idx, *url, payload.as_str()))
+                .collect();
+            if db.enqueue_webhooks(last_id, &deliveries, now).is_err() {
+                return;
+            }
+            self.last_transition_id = last_id;
+            if read < BATCH_SIZE {
+                return;
+            }
+        }
+    }
+
+    /// Attempts every delivery that is due at `now`. Each endpoint gets its
+    /// deliveries in order, while endpoints are sent to side by side, so a
+    /// dead one doesn't hold up the rest.
+    pub async fn deliver_due(&self, now: chrono::DateTime<chrono::Utc>) {
+        let due = {
+            let db = self.state.db.lock().unwrap();
+            db.due_webhooks(&now.to_rfc3339(), BATCH_SIZE)
+                .unwrap_or_default()
+        };
+
+        let mut by_url: Vec<Vec<WebhookDelivery>> = Vec::new();
+        for delivery in due {
+            match by_url.iter_mut().find(|d| d[0].url == delivery.url) {
+                Some(deliveries) => deliveries.push(delivery),
+                None => by_url.push(vec![delivery]),
+            }
+        }
+        futures_util::future::join_all(by_url.into_iter().map(|deliveries| async move {
+            for delivery in deliveries {
+                self.deliver(delivery, now).await;
+            }
+        }))
+        .await;
+    }
+
+    async fn deliver(&self, delivery: WebhookDelivery, now: chrono::DateTime<chrono::Utc>) {
+        let now_str = now.to_rfc3339();
+        // A reordered or edited config leaves a different webhook at that
+        // position, so the url has to match too
+        let webhook = match delivery.webhook {
+            Some(idx) => self.webhooks.get(idx),
+            None => self.webhooks.iter().find(|w| w.url == delivery.url),
+        }
+        .filter(|w| w.url == delivery.url);
+        let result = match webhook {
+            Some(webhook) => self.send(webhook, delivery.id, &delivery.payload).await,
+            None => Err("webhook no longer configured".to_string()),
+        };
+
+        let db = self.state.db.lock().unwrap();
+        let _ = match result {
+            Ok(()) => db.webhook_delivered(delivery.id),
+            Err(error) => {
+                let attempts = delivery.attempts + 1;
+                let retry_at = (webhook.is_some() && attempts < MAX_ATTEMPTS).then(|| {
+                    (now + chrono::Duration::seconds(backoff_secs(delivery.attempts))).to_rfc3339()
+                });
+                db.webhook_attempt_failed(delivery.id, &error, &now_str, retry_at.as_deref())
+            }
+        };
+    }
+
+    async fn send(&self, webhook: &WebhookConfig, id: i64, payload: &str) -> Result<(), String> {
+        let mut request = self
+            .client
+            .post(&webhook.url)
+            .header("content-type", "application/json")
+            .header(DELIVERY_HEADER, id.to_string())
+            .body(payload.to_string());
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, payload.as_bytes()));
+        }
+
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+}
+
+pub fn spawn_webhook_worker(state: Arc<AppState>, webhooks: Vec<WebhookConfig>) {
+    if webhooks.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut rx = state.tx.subscribe();
+        let mut worker = WebhookWorker::new(state, webhooks);
+        loop {
+            let now = chrono::Utc::now();
+            worker.enqueue_new(&now.to_rfc3339());
+            worker.deliver_due(now).await;
+
+            tokio::select! {
+                _ = rx.recv() => {}
+                _ = tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)) => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use axum::{extract::State, http::HeaderMap, http::StatusCode, routing::post, Router};
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Received {
+        fail_next: usize,
+        bodies: Vec<(HeaderMap, String)>,
+    }
+
+    /// Starts a local HTTP server that records webhook requests, answering
+    /// 500 to the first `fail_next` of them.
+    async fn stand_in(fail_next: usize) -> (String, Arc<Mutex<Received>>) {
+        let received = Arc::new(Mutex::new(Received {
+            fail_next,
+            ..Default::default()
+        }));
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(received): State<Arc<Mutex<Received>>>,
+                     headers: HeaderMap,
+                     body: String| async move {
+                        let mut received = received.lock().unwrap();
+                        received.bodies.push((headers, body));
+                        if received.fail_next > 0 {
+                            received.fail_next -= 1;
+                            StatusCode::INTERNAL_SERVER_ERROR
+                        } else {
+                            StatusCode::OK
+                        }
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, received)
+    }
+
+    fn app_state() -> Arc<AppState> {
+        Arc::new(AppState::new(
+            Database::open(Path::new(":memory:")).unwrap(),
+        ))
+    }
+
+    fn record(state: &AppState, session_id: &str, project: &str, status: &str) {
+        let db = state.db.lock().unwrap();
+        db.upsert_plate(
+            session_id,
+            project,
+            None,
+            None,
+            None,
+            status,
+            "stop",
+            None,
+            "2026-01-01T00:00:00+00:00",
+        )
+        .unwrap();
+    }
+
+    fn at(secs: i64) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::from_timestamp(1_767_225_600 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_an_hour() {
+        assert_eq!(backoff_secs(0), 5);
+        assert_eq!(backoff_secs(1), 10);
+        assert_eq!(backoff_secs(3), 40);
+        assert_eq!(backoff_secs(30), MAX_BACKOFF_SECS);
+    }
+
+    #[tokio::test]
+    async fn delivers_matching_transitions_with_signature() {
+        let (url, received) = stand_in(0).await;
+        let state = app_state();
+        let mut worker = WebhookWorker::new(
+            state.clone(),
+            vec![WebhookConfig {
+                url,
+                statuses: vec!["idle".to_string()],
+                projects: vec!["api".to_string()],
+                secret: Some("s3cret".to_string()),
+            }],
+        );
+
+        record(&state, "a", "/work/api", "idle");
+        record(&state, "b", "/work/web", "idle");
+        record(&state, "c", "/work/api", "running");
+        worker.enqueue_new(&at(0).to_rfc3339());
+        worker.deliver_due(at(0)).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.bodies.len(), 1);
+        let (headers, body) = &received.bodies[0];
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["session_id"], "a");
+        assert_eq!(payload["to_status"], "idle");
+        assert_eq!(payload["needs_attention"], true);
+        assert_eq!(
+            headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap(),
+            sign("s3cret", body.as_bytes())
+        );
+        assert_eq!(
+            state.db.lock().unwrap().webhook_health().unwrap().pending,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn a_hanging_endpoint_does_not_hold_up_the_others() {
+        // Accepts connections but never answers
+        let hanging = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (url, received) = stand_in(0).await;
+        let state = app_state();
+        let webhook = |url: String| WebhookConfig {
+            url,
+            statuses: Vec::new(),
+            projects: Vec::new(),
+            secret: None,
+        };
+        let mut worker = WebhookWorker::new(
+            state.clone(),
+            vec![
+                webhook(format!("http://{}/hook", hanging.local_addr().unwrap())),
+                webhook(url),
+            ],
+        );
+
+        record(&state, "a", "/work/api", "idle");
+        worker.enqueue_new(&at(0).to_rfc3339());
+        let _ = tokio::time::timeout(Duration::from_secs(2), worker.deliver_due(at(0))).await;
+        assert_eq!(received.lock().unwrap().bodies.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_deliveries_back_off_and_retry() {
+        let (url, received) = stand_in(1).await;
+        let state = app_state();
+        let mut worker = WebhookWorker::new(
+            state.clone(),
+            vec![WebhookConfig {
+                url,
+                statuses: Vec::new(),
+                projects: Vec::new(),
+                secret: None,
+            }],
+        );
+
+        record(&state, "a", "/work/api", "idle");
+        worker.enqueue_new(&at(0).to_rfc3339());
+        worker.deliver_due(at(0)).await;
+
+        let health = state.db.lock().unwrap().webhook_health().unwrap();
+        assert_eq!(health.pending, 1);
+        assert!(health.last_failure.unwrap().error.contains("500"));
+
+        // Not due again until the backoff has passed
+        worker.deliver_due(at(2)).await;
+        assert_eq!(received.lock().unwrap().bodies.len(), 1);
+
+        worker.deliver_due(at(6)).await;
+        assert_eq!(received.lock().unwrap().bodies.len(), 2);
+        assert_eq!(
+            state.db.lock().unwrap().webhook_health().unwrap().pending,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (url, _received) = stand_in(usize::MAX).await;
+        let state = app_state();
+        let mut worker = WebhookWorker::new(
+            state.clone(),
+            vec![WebhookConfig {
+                url,
+                statuses: Vec::new(),
+                projects: Vec::new(),
+                secret: None,
+            }],
+        );
+
+        record(&state, "a", "/work/api", "idle");
+        worker.enqueue_new(&at(0).to_rfc3339());
+        for attempt in 0..MAX_ATTEMPTS {
+            worker
+                .deliver_due(at(i64::from(attempt) * MAX_BACKOFF_SECS))
+                .await;
+        }
+
+        let health = state.db.lock().unwrap().webhook_health().unwrap();
+        assert_eq!(health.pending, 0);
+        assert_eq!(health.failed, 1);
+    }
+
+    #[tokio::test]
+    async fn webhooks_sharing_a_url_keep_their_own_filter_and_secret() {
+        let (url, received) = stand_in(0).await;
+        let state = app_state();
+        let mut worker = WebhookWorker::new(
+            state.clone(),
+            vec![
+                WebhookConfig {
+                    url: url.clone(),
+                    statuses: vec!["error".to_string()],
+                    projects: Vec::new(),
+                    secret: Some("first".to_string()),
+                },
+                WebhookConfig {
+                    url,
+                    statuses: vec!["idle".to_string()],
+                    projects: Vec::new(),
+                    secret: Some("second".to_string()),
+                },
+            ],
+        );
+
+        record(&state, "a", "/work/api", "idle");
+        worker.enqueue_new(&at(0).to_rfc3339());
+        worker.deliver_due(at(0)).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.bodies.len(), 1);
+        let (headers, body) = &received.bodies[0];
+        assert_eq!(
+            headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap(),
+            sign("second", body.as_bytes())
+        );
+    }
+
+    #[tokio::test]
+    async fn queues_transitions_from_while_the_daemon_was_down() {
+        let state = app_state();
+        let webhooks = vec![WebhookConfig {
+            url: "http://127.0.0.1:9/hook".to_string(),
+            statuses: Vec::new(),
+            projects: Vec::new(),
+            secret: None,
+        }];
+
+        record(&state, "a", "/work/api", "running");
+        let mut worker = WebhookWorker::new(state.clone(), webhooks.clone());
+        record(&state, "a", "/work/api", "idle");
+        worker.enqueue_new(&at(0).to_rfc3339());
+        drop(worker);
+
+        // Recorded with no worker running, then picked up by the next one
+        record(&state, "a", "/work/api", "closed");
+        let mut worker = WebhookWorker::new(state.clone(), webhooks);
+        worker.enqueue_new(&at(1).to_rfc3339());
+        worker.enqueue_new(&at(2).to_rfc3339());
+
+        let db = state.db.lock().unwrap();
+        let queued: Vec<String> = db
+            .due_webhooks(&at(2).to_rfc3339(), BATCH_SIZE)
+            .unwrap()
+            .into_iter()
+            .map(|d| {
+                let payload: serde_json::Value = serde_json::from_str(&d.payload).unwrap();
+                payload["to_status"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(queued, ["idle", "closed"]);
+    }
+}
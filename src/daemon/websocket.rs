@@ -4,9 +4,226 @@ use axum::{
     response::Response,
 };
 use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 
+use super::metrics::ClientGuard;
 use super::state::{AppState, WsMessage};
+use crate::models::Plate;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Messages pushed by the daemon. Every message carries a sequence number
+/// one higher than the previous message on the same connection, so a client
+/// that sees a gap knows it missed something and should ask to resync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Every plate. Sent on connect, after a resync request and whenever the
+    /// daemon itself fell behind. Replaces whatever the client had.
+    Snapshot {
+        protocol: u32,
+        seq: u64,
+        plates: Vec<Plate>,
+    },
+    /// A plate the client hasn't seen yet.
+    Plate {
+        seq: u64,
+        plate: Box<Plate>,
+    },
+    /// The fields that changed on a plate the client already has.
+    PlatePatch {
+        seq: u64,
+        session_id: String,
+        changes: serde_json::Map<String, serde_json::Value>,
+    },
+    PlateDeleted {
+        seq: u64,
+        session_id: String,
+    },
+    /// Something in the inbox changed; clients refetch `GET /inbox`.
+    InboxChanged {
+        seq: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Resync,
+}
+
+impl ServerMessage {
+    pub fn seq(&self) -> u64 {
+        match self {
+            Self::Snapshot { seq, .. }
+            | Self::Plate { seq, .. }
+            | Self::PlatePatch { seq, .. }
+            | Self::PlateDeleted { seq, .. }
+            | Self::InboxChanged { seq } => *seq,
+        }
+    }
+
+    /// Applies this message to a client's plate list, keeping it in the same
+    /// order as `GET /plates` (most recently updated first).
+    pub fn apply(self, plates: &mut Vec<Plate>) {
+        match self {
+            Self::Snapshot { plates: all, .. } => *plates = all,
+            Self::Plate { plate, .. } => {
+                plates.retain(|p| p.session_id != plate.session_id);
+                plates.push(*plate);
+            }
+            Self::PlatePatch {
+                session_id,
+                changes,
+                ..
+            } => {
+                let Some(plate) = plates.iter_mut().find(|p| p.session_id == session_id) else {
+                    return;
+                };
+                let Ok(serde_json::Value::Object(mut fields)) = serde_json::to_value(&*plate)
+                else {
+                    return;
+                };
+                fields.extend(changes);
+                if let Ok(patched) = serde_json::from_value(serde_json::Value::Object(fields)) {
+                    *plate = patched;
+                }
+            }
+            Self::PlateDeleted { session_id, .. } => {
+                plates.retain(|p| p.session_id != session_id);
+            }
+            Self::InboxChanged { .. } => return,
+        }
+        plates.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    }
+}
+
+/// What a client should do with a message it just received.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Received {
+    Apply,
+    /// A message went missing: send `ClientMessage::Resync`.
+    Resync,
+    /// Out of sequence while a resync is already pending.
+    Skip,
+}
+
+/// Client side of the sequence check: accepts messages that follow on from
+/// the last one and asks for one resync per gap.
+#[derive(Debug, Default)]
+pub struct SeqTracker {
+    last_seq: Option<u64>,
+    awaiting_snapshot: bool,
+}
+
+impl SeqTracker {
+    pub fn receive(&mut self, msg: &ServerMessage) -> Received {
+        let in_sequence = matches!(msg, ServerMessage::Snapshot { .. })
+            || self.last_seq.is_some_and(|seq| msg.seq() == seq + 1);
+        if !in_sequence {
+            if self.awaiting_snapshot {
+                return Received::Skip;
+            }
+            self.awaiting_snapshot = true;
+            return Received::Resync;
+        }
+        self.awaiting_snapshot = false;
+        self.last_seq = Some(msg.seq());
+        Received::Apply
+    }
+}
+
+/// Per-connection view of what the client has been sent, used to number
+/// messages and to turn plate updates into field diffs.
+#[derive(Default)]
+struct Session {
+    seq: u64,
+    sent: HashMap<String, serde_json::Map<String, serde_json::Value>>,
+}
+
+fn plate_fields(plate: &Plate) -> serde_json::Map<String, serde_json::Value> {
+    match serde_json::to_value(plate) {
+        Ok(serde_json::Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    }
+}
+
+impl Session {
+    fn next_seq(&mut self) -> u64 {
+        self.seq += 1;
+        self.seq
+    }
+
+    fn snapshot(&mut self, plates: Vec<Plate>) -> ServerMessage {
+        self.sent = plates
+            .iter()
+            .map(|p| (p.session_id.clone(), plate_fields(p)))
+            .collect();
+        ServerMessage::Snapshot {
+            protocol: PROTOCOL_VERSION,
+            seq: self.next_seq(),
+            plates,
+        }
+    }
+
+    fn update(&mut self, plate: Plate) -> Option<ServerMessage> {
+        let fields = plate_fields(&plate);
+        let Some(previous) = self.sent.get(&plate.session_id) else {
+            self.sent.insert(plate.session_id.clone(), fields);
+            return Some(ServerMessage::Plate {
+                seq: self.next_seq(),
+                plate: Box::new(plate),
+            });
+        };
+
+        let changes: serde_json::Map<_, _> = fields
+            .iter()
+            .filter(|(key, value)| previous.get(*key) != Some(*value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if changes.is_empty() {
+            return None;
+        }
+        self.sent.insert(plate.session_id.clone(), fields);
+        Some(ServerMessage::PlatePatch {
+            seq: self.next_seq(),
+            session_id: plate.session_id,
+            changes,
+        })
+    }
+
+    fn deleted(&mut self, session_id: &str) -> Option<ServerMessage> {
+        self.sent.remove(session_id)?;
+        Some(ServerMessage::PlateDeleted {
+            seq: self.next_seq(),
+            session_id: session_id.to_string(),
+        })
+    }
+
+    fn handle(&mut self, state: &AppState, msg: WsMessage) -> Option<ServerMessage> {
+        match msg {
+            WsMessage::PlateUpdate(id) => {
+                let plate = state.find_plate(&id).ok()?;
+                match plate {
+                    Some(plate) => self.update(plate),
+                    None => self.deleted(&id),
+                }
+            }
+            WsMessage::PlateDeleted(id) => self.deleted(&id),
+            WsMessage::InboxUpdate => Some(ServerMessage::InboxChanged {
+                seq: self.next_seq(),
+            }),
+        }
+    }
+
+    fn resync(&mut self, state: &AppState) -> Option<ServerMessage> {
+        let plates = state.all_plates().ok()?;
+        Some(self.snapshot(plates))
+    }
+}
 
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
@@ -16,29 +233,108 @@ pub async fn websocket_handler(
 }
 
 async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
+    let _client = ClientGuard::connect();
     let (mut sender, mut receiver) = socket.split();
+    // Subscribe before taking the snapshot so no update falls in between
     let mut rx = state.tx.subscribe();
+    let mut session = Session::default();
+    let mut outgoing = session.resync(&state);
 
-    let send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            let json = match msg {
-                WsMessage::PlateUpdate(id) => {
-                    serde_json::json!({"type": "plate_update", "session_id": id})
-                }
-                WsMessage::PlateDeleted(id) => {
-                    serde_json::json!({"type": "plate_deleted", "session_id": id})
-                }
+    loop {
+        if let Some(msg) = outgoing.take() {
+            let Ok(json) = serde_json::to_string(&msg) else {
+                continue;
             };
-            if sender.send(Message::Text(json.to_string())).await.is_err() {
+            if sender.send(Message::Text(json)).await.is_err() {
                 break;
             }
         }
-    });
 
-    let recv_task = tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
+        outgoing = tokio::select! {
+            msg = rx.recv() => match msg {
+                Ok(msg) => session.handle(&state, msg),
+                Err(RecvError::Lagged(_)) => session.resync(&state),
+                Err(RecvError::Closed) => break,
+            },
+            incoming = receiver.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Resync) => session.resync(&state),
+                        Err(_) => None,
+                    }
+                }
+                Some(Ok(_)) => None,
+                _ => break,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PlateStatus;
+
+    fn plate(session_id: &str, status: PlateStatus, updated_at: &str) -> Plate {
+        Plate {
+            created_at: "2026-01-01T00:00:00+00:00".to_string(),
+            updated_at: updated_at.to_string(),
+            ..Plate::for_test(session_id, "/work/api", status)
+        }
+    }
+
+    #[test]
+    fn updates_become_numbered_diffs() {
+        let mut session = Session::default();
+        let snapshot = session.snapshot(vec![plate("a", PlateStatus::Running, "t1")]);
+        assert_eq!(snapshot.seq(), 1);
+
+        assert!(session
+            .update(plate("a", PlateStatus::Running, "t1"))
+            .is_none());
+
+        match session.update(plate("a", PlateStatus::Idle, "t2")) {
+            Some(ServerMessage::PlatePatch { seq, changes, .. }) => {
+                assert_eq!(seq, 2);
+                let mut keys: Vec<_> = changes.keys().cloned().collect();
+                keys.sort();
+                assert_eq!(keys, vec!["status", "updated_at"]);
+            }
+            other => panic!("expected patch, got {:?}", other),
+        }
+
+        assert!(matches!(
+            session.update(plate("b", PlateStatus::Running, "t3")),
+            Some(ServerMessage::Plate { seq: 3, .. })
+        ));
+        assert!(matches!(
+            session.deleted("b"),
+            Some(ServerMessage::PlateDeleted { seq: 4, .. })
+        ));
+        assert!(session.deleted("b").is_none());
+    }
+
+    #[test]
+    fn client_applies_messages_in_order() {
+        let mut session = Session::default();
+        let mut plates = Vec::new();
+
+        let messages = vec![
+            session.snapshot(vec![plate("a", PlateStatus::Running, "t1")]),
+            session
+                .update(plate("b", PlateStatus::Running, "t2"))
+                .unwrap(),
+            session.update(plate("a", PlateStatus::Idle, "t3")).unwrap(),
+            session.deleted("b").unwrap(),
+        ];
+        for msg in messages {
+            let msg: ServerMessage =
+                serde_json::from_str(&serde_json::to_string(&msg).unwrap()).unwrap();
+            msg.apply(&mut plates);
+        }
 
-    tokio::select! {
-        _ = send_task => {},
-        _ = recv_task => {},
+        assert_eq!(plates.len(), 1);
+        assert_eq!(plates[0].status, PlateStatus::Idle);
+        assert_eq!(plates[0].updated_at, "t3");
     }
 }
@@ -1,7 +1,19 @@
 use anyhow::Result;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
 use std::path::Path;
 
+use crate::models::{
+    DailyUsage, Digest, DigestEntry, InboxItem, InboxStatus, ModelUsage, PlateDetail, PlateEvent,
+    ProjectUsage, Todo, TokenUsage, UsageReport, UsageTotals,
+};
+
+/// Most entries [`Database::get_digest`] returns; older ones are dropped.
+pub const DIGEST_LIMIT: usize = 100;
+/// How many events and tool calls [`Database::get_plate_detail`] includes.
+pub const RECENT_EVENTS: usize = 20;
+pub const RECENT_TOOL_CALLS: usize = 10;
+
 const SCHEMA: &str = r#"
 CREATE TABLE IF NOT EXISTS plates (
     session_id TEXT PRIMARY KEY,
@@ -30,10 +42,396 @@ CREATE TABLE IF NOT EXISTS events (
     created_at TEXT NOT NULL
 );
 
+CREATE TABLE IF NOT EXISTS transitions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook INTEGER,
+    url TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    state TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT NOT NULL,
+    last_attempt_at TEXT,
+    last_error TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS cursors (
+    name TEXT PRIMARY KEY,
+    position INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS inbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    type TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT,
+    context TEXT,
+    status TEXT NOT NULL DEFAULT 'pending',
+    snoozed_until TEXT,
+    dispatched_to TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS summaries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS todo_completions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    completed_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS token_usage (
+    session_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    day TEXT NOT NULL,
+    input_tokens INTEGER NOT NULL DEFAULT 0,
+    output_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+    cost_usd REAL NOT NULL DEFAULT 0,
+    PRIMARY KEY (session_id, model, day)
+);
+
+CREATE TABLE IF NOT EXISTS dismissed_plates (
+    session_id TEXT PRIMARY KEY
+);
+
 CREATE INDEX IF NOT EXISTS idx_plates_status ON plates(status);
+CREATE INDEX IF NOT EXISTS idx_inbox_status ON inbox(status);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(state, next_attempt_at);
 CREATE INDEX IF NOT EXISTS idx_events_session ON events(session_id);
+CREATE INDEX IF NOT EXISTS idx_transitions_session ON transitions(session_id);
+CREATE INDEX IF NOT EXISTS idx_summaries_session ON summaries(session_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_todo_completions_session ON todo_completions(session_id);
+"#;
+
+const SEARCH_SCHEMA: &str = r#"
+CREATE VIRTUAL TABLE IF NOT EXISTS plates_fts USING fts5(
+    session_id UNINDEXED,
+    goal,
+    summary,
+    project_path,
+    git_branch,
+    prompts
+);
+
+CREATE TRIGGER IF NOT EXISTS plates_fts_insert AFTER INSERT ON plates BEGIN
+    INSERT INTO plates_fts (session_id, goal, summary, project_path, git_branch)
+    VALUES (new.session_id, new.goal, new.summary, new.project_path, new.git_branch);
+END;
+
+CREATE TRIGGER IF NOT EXISTS plates_fts_update AFTER UPDATE OF goal, summary, project_path, git_branch ON plates BEGIN
+    UPDATE plates_fts
+    SET goal = new.goal, summary = new.summary, project_path = new.project_path, git_branch = new.git_branch
+    WHERE session_id = new.session_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS plates_fts_delete AFTER DELETE ON plates BEGIN
+    DELETE FROM plates_fts WHERE session_id = old.session_id;
+END;
 "#;
 
+const PLATE_COLUMNS: &str = r#"s.session_id, s.project_path, s.git_branch, s.status,
+                      s.last_event_type, s.last_tool, s.summary, s.created_at, s.updated_at,
+                      s.transcript_path, s.tmux_target, t.todos_json,
+                      (SELECT COALESCE(SUM(input_tokens + output_tokens + cache_creation_tokens
+                                           + cache_read_tokens), 0)
+                       FROM token_usage u WHERE u.session_id = s.session_id),
+                      (SELECT COALESCE(SUM(cost_usd), 0.0)
+                       FROM token_usage u WHERE u.session_id = s.session_id),
+                      s.pending_questions, s.plan IS NOT NULL"#;
+
+fn plate_from_row(row: &rusqlite::Row) -> rusqlite::Result<crate::models::Plate> {
+    let todos_json: Option<String> = row.get(11)?;
+    let todo_progress = todos_json.and_then(|json| {
+        serde_json::from_str::<Vec<serde_json::Value>>(&json)
+            .ok()
+            .map(|todos| {
+                let completed = todos
+                    .iter()
+                    .filter(|t| t.get("status").and_then(|s| s.as_str()) == Some("completed"))
+                    .count();
+                format!("{}/{}", completed, todos.len())
+            })
+    });
+
+    let status_str: String = row.get(3)?;
+    let status = status_str.parse().unwrap_or_default();
+
+    Ok(crate::models::Plate {
+        session_id: row.get(0)?,
+        project_path: row.get(1)?,
+        git_branch: row.get(2)?,
+        tmux_target: row.get(10)?,
+        host: None,
+        read_only: false,
+        status,
+        last_event_type: row.get(4)?,
+        last_tool: row.get(5)?,
+        summary: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+        transcript_path: row.get(9)?,
+        todo_progress,
+        tokens: row.get::<_, i64>(12)? as u64,
+        cost_usd: row.get(13)?,
+        pending_questions: row
+            .get::<_, Option<String>>(14)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        has_plan: row.get(15)?,
+    })
+}
+
+fn plate_event_from_row(row: &rusqlite::Row) -> rusqlite::Result<crate::models::PlateEvent> {
+    let payload: String = row.get(2)?;
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap_or_default();
+    let text = |key: &str| payload.get(key).and_then(|v| v.as_str()).map(String::from);
+
+    Ok(crate::models::PlateEvent {
+        id: row.get(0)?,
+        event_type: row.get(1)?,
+        tool_name: text("tool_name"),
+        tool_params: payload.get("tool_params").filter(|v| !v.is_null()).cloned(),
+        error: text("error"),
+        created_at: row.get(3)?,
+    })
+}
+
+const INBOX_COLUMNS: &str =
+    "id, type, title, body, context, status, snoozed_until, dispatched_to, created_at, updated_at";
+
+fn inbox_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<InboxItem> {
+    let context: Option<String> = row.get(4)?;
+    let status: String = row.get(5)?;
+    Ok(InboxItem {
+        id: row.get(0)?,
+        item_type: row.get(1)?,
+        title: row.get(2)?,
+        body: row.get(3)?,
+        context: context.and_then(|c| serde_json::from_str(&c).ok()),
+        status: status.parse().unwrap_or_default(),
+        snoozed_until: row.get(6)?,
+        dispatched_to: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+/// How long a connection waits on another one's lock before giving up.
+/// Pages copied per backup step. Writers on other connections get the
+/// database back between steps.
+const BACKUP_STEP_PAGES: i32 = 100;
+
+/// Copies the database open on `src` to `path` a few pages at a time, with
+/// the SQLite online backup API.
+pub fn backup_connection(src: &Connection, path: &Path) -> Result<()> {
+    let mut dst = Connection::open(path)?;
+    let backup = rusqlite::backup::Backup::new(src, &mut dst)?;
+    backup.run_to_completion(
+        BACKUP_STEP_PAGES,
+        std::time::Duration::from_millis(10),
+        None,
+    )?;
+    Ok(())
+}
+
+pub const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+pub fn integrity_problems(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let results: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(results.into_iter().filter(|r| r != "ok").collect())
+}
+
+/// Accepts an RFC 3339 timestamp or a plain `YYYY-MM-DD` date (midnight UTC)
+/// and normalizes it to the format timestamps are stored in, so the result
+/// can be compared against stored timestamps as a string.
+pub fn parse_since(s: &str) -> Result<String> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&chrono::Utc).to_rfc3339());
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        let dt = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        return Ok(dt.to_rfc3339());
+    }
+    anyhow::bail!("Invalid date: {} (use YYYY-MM-DD or RFC 3339)", s)
+}
+
+const USAGE_SUMS: &str = "SUM(input_tokens), SUM(output_tokens), SUM(cache_creation_tokens), \
+                          SUM(cache_read_tokens), SUM(cost_usd)";
+
+/// Reads the five columns of [`USAGE_SUMS`] starting at `first`.
+fn usage_totals(row: &rusqlite::Row, first: usize) -> rusqlite::Result<UsageTotals> {
+    let count = |i: usize| row.get::<_, i64>(first + i).map(|n| n as u64);
+    Ok(UsageTotals {
+        tokens: TokenUsage {
+            input_tokens: count(0)?,
+            output_tokens: count(1)?,
+            cache_creation_tokens: count(2)?,
+            cache_read_tokens: count(3)?,
+        },
+        cost_usd: row.get(first + 4)?,
+    })
+}
+
+/// Turns free-form user input into an FTS5 query where every word is a
+/// quoted prefix match, so punctuation can't produce syntax errors.
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Transition {
+    pub id: i64,
+    pub session_id: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub created_at: String,
+}
+
+impl Transition {
+    pub fn needs_attention(&self) -> bool {
+        self.to_status
+            .parse::<crate::models::PlateStatus>()
+            .is_ok_and(|s| s.needs_attention())
+    }
+}
+
+/// A transition together with where it leaves the plate. This is the payload
+/// pushed to SSE clients and webhooks.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusChange {
+    #[serde(flatten)]
+    pub transition: Transition,
+    pub needs_attention: bool,
+    pub plate: Option<crate::models::Plate>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    /// Position of the webhook in `[[webhooks]]`; `None` for deliveries
+    /// queued before this was recorded.
+    pub webhook: Option<usize>,
+    pub url: String,
+    pub payload: String,
+    pub attempts: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookFailure {
+    pub url: String,
+    pub error: String,
+    pub at: String,
+}
+
+/// Webhook queue state, reported through `/status`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WebhookHealth {
+    pub pending: i64,
+    pub failed: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failure: Option<WebhookFailure>,
+}
+
+/// How many permanently failed deliveries are kept for inspection.
+const FAILED_DELIVERIES_TO_KEEP: i64 = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredEvent {
+    pub id: i64,
+    pub session_id: String,
+    pub event_type: String,
+    pub payload: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlateSort {
+    #[default]
+    UpdatedAt,
+    CreatedAt,
+    Project,
+    Status,
+}
+
+impl PlateSort {
+    fn column(&self) -> &'static str {
+        match self {
+            Self::UpdatedAt => "s.updated_at",
+            Self::CreatedAt => "s.created_at",
+            Self::Project => "s.project_path",
+            Self::Status => "s.status",
+        }
+    }
+
+    /// The value of this sort key for a plate, as stored in the database.
+    pub fn key(&self, plate: &crate::models::Plate) -> String {
+        match self {
+            Self::UpdatedAt => plate.updated_at.clone(),
+            Self::CreatedAt => plate.created_at.clone(),
+            Self::Project => plate.project_path.clone(),
+            Self::Status => plate.status.as_str().to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for PlateSort {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "updated_at" => Ok(Self::UpdatedAt),
+            "created_at" => Ok(Self::CreatedAt),
+            "project" => Ok(Self::Project),
+            "status" => Ok(Self::Status),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Filters and keyset pagination for [`Database::query_plates`]. `after` is
+/// the sort key and session id of the last plate on the previous page.
+#[derive(Debug, Clone, Default)]
+pub struct PlateQuery {
+    pub statuses: Vec<crate::models::PlateStatus>,
+    pub project: Option<String>,
+    pub branch: Option<String>,
+    pub since: Option<String>,
+    pub sort: PlateSort,
+    pub ascending: bool,
+    pub after: Option<(String, String)>,
+    pub limit: usize,
+}
+
 pub struct Database {
     conn: Connection,
 }
@@ -44,6 +442,7 @@ impl Database {
             std::fs::create_dir_all(parent)?;
         }
         let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
         let db = Self { conn };
         db.init_schema()?;
         Ok(db)
@@ -83,6 +482,52 @@ impl Database {
             self.conn
                 .execute("ALTER TABLE plates ADD COLUMN goal TEXT", [])?;
         }
+        if !columns.contains(&"summary_at".to_string()) {
+            self.conn
+                .execute("ALTER TABLE plates ADD COLUMN summary_at TEXT", [])?;
+        }
+        if !columns.contains(&"acknowledged_at".to_string()) {
+            self.conn
+                .execute("ALTER TABLE plates ADD COLUMN acknowledged_at TEXT", [])?;
+        }
+        if !columns.contains(&"pending_questions".to_string()) {
+            self.conn
+                .execute("ALTER TABLE plates ADD COLUMN pending_questions TEXT", [])?;
+        }
+        if !columns.contains(&"plan".to_string()) {
+            self.conn
+                .execute("ALTER TABLE plates ADD COLUMN plan TEXT", [])?;
+        }
+        let delivery_columns: Vec<String> = self
+            .conn
+            .prepare("PRAGMA table_info(webhook_deliveries)")?
+            .query_map([], |row| row.get(1))?
+            .filter_map(|r| r.ok())
+            .collect();
+        if !delivery_columns.contains(&"webhook".to_string()) {
+            self.conn.execute(
+                "ALTER TABLE webhook_deliveries ADD COLUMN webhook INTEGER",
+                [],
+            )?;
+        }
+
+        // However a plate stops waiting, its question is no longer pending
+        self.conn.execute_batch(
+            r#"CREATE TRIGGER IF NOT EXISTS plates_clear_questions
+               AFTER UPDATE OF status ON plates
+               WHEN new.status != 'awaiting_input' AND new.pending_questions IS NOT NULL
+               BEGIN
+                   UPDATE plates SET pending_questions = NULL WHERE session_id = new.session_id;
+               END;"#,
+        )?;
+
+        self.conn.execute_batch(SEARCH_SCHEMA)?;
+        self.conn.execute(
+            "INSERT INTO plates_fts (session_id, goal, summary, project_path, git_branch)
+             SELECT session_id, goal, summary, project_path, git_branch FROM plates
+             WHERE session_id NOT IN (SELECT session_id FROM plates_fts)",
+            [],
+        )?;
         Ok(())
     }
 
@@ -90,6 +535,11 @@ impl Database {
         &self.conn
     }
 
+    /// Copies the live database to `path` with the SQLite online backup API.
+    pub fn backup_to(&self, path: &Path) -> Result<()> {
+        backup_connection(&self.conn, path)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn upsert_plate(
         &self,
@@ -103,14 +553,7 @@ impl Database {
         tool_name: Option<&str>,
         now: &str,
     ) -> Result<bool> {
-        let existing: Option<String> = self
-            .conn
-            .query_row(
-                "SELECT session_id FROM plates WHERE session_id = ?",
-                [session_id],
-                |row| row.get(0),
-            )
-            .ok();
+        let existing = self.get_status(session_id)?;
 
         if existing.is_none() {
             let placeholder_id = format!("pending:{}", project_path);
@@ -120,16 +563,360 @@ impl Database {
                 "INSERT INTO plates (session_id, project_path, transcript_path, git_branch, tmux_target, status, last_event_type, last_tool, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 params![session_id, project_path, transcript_path, git_branch, tmux_target, status, event_type, tool_name, now, now],
             )?;
+            self.record_transition(session_id, None, status, now)?;
             Ok(false)
         } else {
             self.conn.execute(
                 "UPDATE plates SET status = ?, last_event_type = ?, last_tool = COALESCE(?, last_tool), transcript_path = COALESCE(?, transcript_path), git_branch = COALESCE(?, git_branch), tmux_target = COALESCE(?, tmux_target), updated_at = ? WHERE session_id = ?",
                 params![status, event_type, tool_name, transcript_path, git_branch, tmux_target, now, session_id],
             )?;
+            self.record_transition(session_id, existing.as_deref(), status, now)?;
             Ok(true)
         }
     }
 
+    /// Inserts a plate reconstructed from a transcript as closed. Returns false
+    /// without touching anything if the session is already known.
+    #[allow(clippy::too_many_arguments)]
+    pub fn import_plate(
+        &self,
+        session_id: &str,
+        project_path: &str,
+        transcript_path: &str,
+        git_branch: Option<&str>,
+        summary: Option<&str>,
+        created_at: &str,
+        updated_at: &str,
+    ) -> Result<bool> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO plates (session_id, project_path, transcript_path, git_branch, status, last_event_type, summary, created_at, updated_at) VALUES (?, ?, ?, ?, 'closed', 'import', ?, ?, ?)",
+            params![session_id, project_path, transcript_path, git_branch, summary, created_at, updated_at],
+        )?;
+        if inserted == 0 {
+            return Ok(false);
+        }
+        self.record_transition(session_id, None, "closed", updated_at)?;
+        Ok(true)
+    }
+
+    /// Whether a plate for this session was deleted from the dashboard.
+    pub fn is_dismissed(&self, session_id: &str) -> Result<bool> {
+        self.conn
+            .query_row(
+                "SELECT 1 FROM dismissed_plates WHERE session_id = ?",
+                [session_id],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|found| found.is_some())
+            .map_err(Into::into)
+    }
+
+    pub fn get_status(&self, session_id: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT status FROM plates WHERE session_id = ?",
+                [session_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    pub fn set_status(&self, session_id: &str, status: &str, now: &str) -> Result<()> {
+        let previous = self.get_status(session_id)?;
+        self.conn.execute(
+            "UPDATE plates SET status = ?, updated_at = ? WHERE session_id = ?",
+            params![status, now, session_id],
+        )?;
+        self.record_transition(session_id, previous.as_deref(), status, now)
+    }
+
+    fn record_transition(
+        &self,
+        session_id: &str,
+        from_status: Option<&str>,
+        to_status: &str,
+        now: &str,
+    ) -> Result<()> {
+        if from_status == Some(to_status) {
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT INTO transitions (session_id, from_status, to_status, created_at) VALUES (?, ?, ?, ?)",
+            params![session_id, from_status, to_status, now],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_transitions(&self) -> Result<Vec<Transition>> {
+        self.get_transitions_after(0, usize::MAX)
+    }
+
+    /// Transitions with an id greater than `after_id`, oldest first.
+    pub fn get_transitions_after(&self, after_id: i64, limit: usize) -> Result<Vec<Transition>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, from_status, to_status, created_at FROM transitions WHERE id > ? ORDER BY id LIMIT ?",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![after_id, limit], |row| {
+            Ok(Transition {
+                id: row.get(0)?,
+                session_id: row.get(1)?,
+                from_status: row.get(2)?,
+                to_status: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    pub fn count_plates_by_status(&self) -> Result<Vec<(String, i64)>> {
+        self.count_grouped("SELECT status, COUNT(*) FROM plates GROUP BY status")
+    }
+
+    fn count_grouped(&self, sql: &str) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    pub fn size_bytes(&self) -> Result<i64> {
+        self.conn
+            .query_row(
+                "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(Into::into)
+    }
+
+    pub fn status_change(&self, transition: Transition) -> Result<StatusChange> {
+        Ok(StatusChange {
+            plate: self.get_plate(&transition.session_id)?,
+            needs_attention: transition.needs_attention(),
+            transition,
+        })
+    }
+
+    /// The last transition the webhook worker has queued deliveries for.
+    pub fn webhook_cursor(&self) -> Result<Option<i64>> {
+        self.conn
+            .query_row(
+                "SELECT position FROM cursors WHERE name = 'webhooks'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Queues `deliveries` (webhook position, url and payload) and moves the webhook cursor to
+    /// `last_transition_id` in one transaction, so a transition is never
+    /// queued twice or skipped.
+    pub fn enqueue_webhooks(
+        &self,
+        last_transition_id: i64,
+        deliveries: &[(usize, &str, &str)],
+        now: &str,
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (webhook, url, payload) in deliveries {
+            tx.execute(
+                "INSERT INTO webhook_deliveries (webhook, url, payload, next_attempt_at, created_at) VALUES (?, ?, ?, ?, ?)",
+                params![*webhook as i64, url, payload, now, now],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO cursors (name, position) VALUES ('webhooks', ?)",
+            [last_transition_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Pending deliveries whose next attempt is due at `now`, oldest first.
+    pub fn due_webhooks(&self, now: &str, limit: usize) -> Result<Vec<WebhookDelivery>> {
+        let mut stmt = self.conn.prepare(
+            r#"SELECT id, webhook, url, payload, attempts FROM webhook_deliveries
+               WHERE state = 'pending' AND next_attempt_at <= ?
+               ORDER BY id LIMIT ?"#,
+        )?;
+        let rows = stmt.query_map(params![now, limit as i64], |row| {
+            Ok(WebhookDelivery {
+                id: row.get(0)?,
+                webhook: row
+                    .get::<_, Option<i64>>(1)?
+                    .and_then(|i| usize::try_from(i).ok()),
+                url: row.get(2)?,
+                payload: row.get(3)?,
+                attempts: row.get(4)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    pub fn webhook_delivered(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM webhook_deliveries WHERE id = ?", [id])?;
+        Ok(())
+    }
+
+    /// Records a failed attempt. With `retry_at` the delivery is retried
+    /// then; without it the delivery is given up on.
+    pub fn webhook_attempt_failed(
+        &self,
+        id: i64,
+        error: &str,
+        now: &str,
+        retry_at: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            r#"UPDATE webhook_deliveries
+               SET attempts = attempts + 1, last_attempt_at = ?, last_error = ?,
+                   state = CASE WHEN ? IS NULL THEN 'failed' ELSE 'pending' END,
+                   next_attempt_at = COALESCE(?, next_attempt_at)
+               WHERE id = ?"#,
+            params![now, error, retry_at, retry_at, id],
+        )?;
+        self.conn.execute(
+            r#"DELETE FROM webhook_deliveries WHERE state = 'failed' AND id NOT IN
+               (SELECT id FROM webhook_deliveries WHERE state = 'failed' ORDER BY id DESC LIMIT ?)"#,
+            [FAILED_DELIVERIES_TO_KEEP],
+        )?;
+        Ok(())
+    }
+
+    pub fn webhook_health(&self) -> Result<WebhookHealth> {
+        let (pending, failed) = self.conn.query_row(
+            r#"SELECT COALESCE(SUM(state = 'pending'), 0), COALESCE(SUM(state = 'failed'), 0)
+               FROM webhook_deliveries"#,
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let last_failure = self
+            .conn
+            .query_row(
+                r#"SELECT url, last_error, last_attempt_at FROM webhook_deliveries
+                   WHERE last_error IS NOT NULL ORDER BY last_attempt_at DESC LIMIT 1"#,
+                [],
+                |row| {
+                    Ok(WebhookFailure {
+                        url: row.get(0)?,
+                        error: row.get(1)?,
+                        at: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(WebhookHealth {
+            pending,
+            failed,
+            last_failure,
+        })
+    }
+
+    pub fn insert_inbox_item(
+        &self,
+        item_type: &str,
+        title: &str,
+        body: Option<&str>,
+        context: Option<&serde_json::Value>,
+        now: &str,
+    ) -> Result<InboxItem> {
+        self.conn.execute(
+            "INSERT INTO inbox (type, title, body, context, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                item_type,
+                title,
+                body,
+                context.map(|c| c.to_string()),
+                now,
+                now
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.get_inbox_item(id)?
+            .ok_or_else(|| anyhow::anyhow!("Inbox item {} vanished after insert", id))
+    }
+
+    pub fn get_inbox_item(&self, id: i64) -> Result<Option<InboxItem>> {
+        self.conn
+            .query_row(
+                &format!("SELECT {} FROM inbox WHERE id = ?", INBOX_COLUMNS),
+                [id],
+                inbox_item_from_row,
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Inbox items in any of `statuses` (all items when empty), oldest first.
+    /// Snoozed items whose time has come are moved back to pending first.
+    pub fn list_inbox(&self, statuses: &[InboxStatus], now: &str) -> Result<Vec<InboxItem>> {
+        self.conn.execute(
+            "UPDATE inbox SET status = 'pending', snoozed_until = NULL, updated_at = ?1 WHERE status = 'snoozed' AND snoozed_until <= ?1",
+            [now],
+        )?;
+
+        let placeholders = vec!["?"; statuses.len()].join(", ");
+        let sql = if statuses.is_empty() {
+            format!("SELECT {} FROM inbox ORDER BY id", INBOX_COLUMNS)
+        } else {
+            format!(
+                "SELECT {} FROM inbox WHERE status IN ({}) ORDER BY id",
+                INBOX_COLUMNS, placeholders
+            )
+        };
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            rusqlite::params_from_iter(statuses.iter().map(|s| s.as_str())),
+            inbox_item_from_row,
+        )?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    pub fn update_inbox_item(
+        &self,
+        id: i64,
+        status: InboxStatus,
+        snoozed_until: Option<&str>,
+        dispatched_to: Option<&str>,
+        now: &str,
+    ) -> Result<bool> {
+        let updated = self.conn.execute(
+            r#"UPDATE inbox SET status = ?, snoozed_until = ?,
+                   dispatched_to = COALESCE(?, dispatched_to), updated_at = ?
+               WHERE id = ?"#,
+            params![status.as_str(), snoozed_until, dispatched_to, now, id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    pub fn latest_transition_id(&self) -> Result<i64> {
+        self.conn
+            .query_row("SELECT COALESCE(MAX(id), 0) FROM transitions", [], |row| {
+                row.get(0)
+            })
+            .map_err(Into::into)
+    }
+
+    pub fn get_events(&self) -> Result<Vec<StoredEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, event_type, payload, created_at FROM events ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(StoredEvent {
+                id: row.get(0)?,
+                session_id: row.get(1)?,
+                event_type: row.get(2)?,
+                payload: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
     pub fn insert_event(
         &self,
         session_id: &str,
@@ -144,7 +931,21 @@ impl Database {
         Ok(())
     }
 
+    /// Replaces the plate's todo list, recording items that became completed.
     pub fn upsert_todos(&self, session_id: &str, todos_json: &str, now: &str) -> Result<()> {
+        let before = self.get_todos(session_id)?;
+        let after: Vec<Todo> = serde_json::from_str(todos_json).unwrap_or_default();
+        for todo in after.iter().filter(|t| t.status == "completed") {
+            let was_done = before
+                .iter()
+                .any(|b| b.content == todo.content && b.status == "completed");
+            if !was_done {
+                self.conn.execute(
+                    "INSERT INTO todo_completions (session_id, content, completed_at) VALUES (?, ?, ?)",
+                    params![session_id, todo.content, now],
+                )?;
+            }
+        }
         self.conn.execute(
             "INSERT OR REPLACE INTO todos (session_id, todos_json, updated_at) VALUES (?, ?, ?)",
             params![session_id, todos_json, now],
@@ -152,54 +953,220 @@ impl Database {
         Ok(())
     }
 
+    pub fn get_todos(&self, session_id: &str) -> Result<Vec<Todo>> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT todos_json FROM todos WHERE session_id = ?",
+                [session_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
     pub fn get_plates(&self) -> Result<Vec<crate::models::Plate>> {
-        let mut stmt = self.conn.prepare(
-            r#"SELECT s.session_id, s.project_path, s.git_branch, s.status,
-                      s.last_event_type, s.last_tool, s.summary, s.created_at, s.updated_at,
-                      s.transcript_path, s.tmux_target, t.todos_json
+        let mut stmt = self.conn.prepare(&format!(
+            r#"SELECT {}
                FROM plates s
                LEFT JOIN todos t ON s.session_id = t.session_id
                ORDER BY s.updated_at DESC"#,
+            PLATE_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map([], plate_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    pub fn get_plate(&self, session_id: &str) -> Result<Option<crate::models::Plate>> {
+        self.conn
+            .query_row(
+                &format!(
+                    r#"SELECT {}
+                       FROM plates s
+                       LEFT JOIN todos t ON s.session_id = t.session_id
+                       WHERE s.session_id = ?"#,
+                    PLATE_COLUMNS
+                ),
+                [session_id],
+                plate_from_row,
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    pub fn get_plate_detail(&self, session_id: &str) -> Result<Option<PlateDetail>> {
+        let found = self
+            .conn
+            .query_row(
+                &format!(
+                    r#"SELECT {}, s.goal AS detail_goal, t.todos_json AS detail_todos,
+                              t.updated_at AS detail_todos_updated_at, s.plan AS detail_plan
+                       FROM plates s
+                       LEFT JOIN todos t ON s.session_id = t.session_id
+                       WHERE s.session_id = ?"#,
+                    PLATE_COLUMNS
+                ),
+                [session_id],
+                // By name, as the plate's own columns come first and grow
+                |row| {
+                    Ok((
+                        plate_from_row(row)?,
+                        row.get::<_, Option<String>>("detail_goal")?,
+                        row.get::<_, Option<String>>("detail_todos")?,
+                        row.get::<_, Option<String>>("detail_todos_updated_at")?,
+                        row.get::<_, Option<String>>("detail_plan")?,
+                    ))
+                },
+            )
+            .optional()?;
+        let Some((plate, goal, todos_json, todos_updated_at, plan)) = found else {
+            return Ok(None);
+        };
+
+        // Skip entries that don't parse rather than losing the whole list
+        let todos = todos_json
+            .and_then(|json| serde_json::from_str::<Vec<serde_json::Value>>(&json).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|todo| serde_json::from_value(todo).ok())
+            .collect();
+
+        Ok(Some(PlateDetail {
+            plate,
+            goal,
+            todos,
+            todos_updated_at,
+            recent_events: self.get_recent_events(session_id, None, RECENT_EVENTS)?,
+            recent_tool_calls: self.get_recent_events(
+                session_id,
+                Some("tool_start"),
+                RECENT_TOOL_CALLS,
+            )?,
+            usage: self.get_plate_usage(session_id)?,
+            plan,
+            status_since: self.conn.query_row(
+                "SELECT MAX(created_at) FROM transitions WHERE session_id = ?",
+                [session_id],
+                |row| row.get(0),
+            )?,
+        }))
+    }
+
+    /// Newest events for a plate first, optionally only those of one type.
+    pub fn get_recent_events(
+        &self,
+        session_id: &str,
+        event_type: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<PlateEvent>> {
+        let mut stmt = self.conn.prepare(
+            r#"SELECT id, event_type, payload, created_at FROM events
+               WHERE session_id = ?1 AND (?2 IS NULL OR event_type = ?2)
+               ORDER BY id DESC LIMIT ?3"#,
+        )?;
+        let rows = stmt.query_map(
+            params![session_id, event_type, limit as i64],
+            plate_event_from_row,
         )?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
 
-        let rows = stmt.query_map([], |row| {
-            let todos_json: Option<String> = row.get(11)?;
-            let todo_progress = todos_json.and_then(|json| {
-                serde_json::from_str::<Vec<serde_json::Value>>(&json)
-                    .ok()
-                    .map(|todos| {
-                        let completed = todos
-                            .iter()
-                            .filter(|t| {
-                                t.get("status").and_then(|s| s.as_str()) == Some("completed")
-                            })
-                            .count();
-                        format!("{}/{}", completed, todos.len())
-                    })
-            });
+    pub fn query_plates(&self, query: &PlateQuery) -> Result<Vec<crate::models::Plate>> {
+        use rusqlite::types::Value;
 
-            let status_str: String = row.get(3)?;
-            let status = status_str.parse().unwrap_or_default();
-
-            Ok(crate::models::Plate {
-                session_id: row.get(0)?,
-                project_path: row.get(1)?,
-                git_branch: row.get(2)?,
-                tmux_target: row.get(10)?,
-                status,
-                last_event_type: row.get(4)?,
-                last_tool: row.get(5)?,
-                summary: row.get(6)?,
-                created_at: row.get(7)?,
-                updated_at: row.get(8)?,
-                transcript_path: row.get(9)?,
-                todo_progress,
-            })
-        })?;
+        let mut clauses: Vec<String> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
 
+        if !query.statuses.is_empty() {
+            let placeholders = vec!["?"; query.statuses.len()].join(", ");
+            clauses.push(format!("s.status IN ({})", placeholders));
+            values.extend(
+                query
+                    .statuses
+                    .iter()
+                    .map(|st| Value::Text(st.as_str().to_string())),
+            );
+        }
+        if let Some(project) = &query.project {
+            let project = project.trim_end_matches('/').to_string();
+            clauses.push(
+                "(s.project_path = ? OR substr(s.project_path, -length(?) - 1) = '/' || ?)"
+                    .to_string(),
+            );
+            values.extend(std::iter::repeat_n(Value::Text(project), 3));
+        }
+        if let Some(branch) = &query.branch {
+            clauses.push("s.git_branch = ?".to_string());
+            values.push(Value::Text(branch.clone()));
+        }
+        if let Some(since) = &query.since {
+            clauses.push("s.updated_at >= ?".to_string());
+            values.push(Value::Text(since.clone()));
+        }
+
+        let column = query.sort.column();
+        let (direction, comparison) = if query.ascending {
+            ("ASC", ">")
+        } else {
+            ("DESC", "<")
+        };
+        if let Some((key, session_id)) = &query.after {
+            clauses.push(format!("({}, s.session_id) {} (?, ?)", column, comparison));
+            values.push(Value::Text(key.clone()));
+            values.push(Value::Text(session_id.clone()));
+        }
+
+        let where_clause = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+        values.push(Value::Integer(query.limit as i64));
+
+        let mut stmt = self.conn.prepare(&format!(
+            r#"SELECT {}
+               FROM plates s
+               LEFT JOIN todos t ON s.session_id = t.session_id
+               {}
+               ORDER BY {} {}, s.session_id {}
+               LIMIT ?"#,
+            PLATE_COLUMNS, where_clause, column, direction, direction
+        ))?;
+
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), plate_from_row)?;
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
+    pub fn search_plates(&self, query: &str) -> Result<Vec<crate::models::Plate>> {
+        let Some(fts) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            r#"SELECT {}
+               FROM plates_fts f
+               JOIN plates s ON s.session_id = f.session_id
+               LEFT JOIN todos t ON s.session_id = t.session_id
+               WHERE plates_fts MATCH ?
+               ORDER BY f.rank"#,
+            PLATE_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map([fts], plate_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    pub fn set_search_prompts(&self, session_id: &str, prompts: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE plates_fts SET prompts = ? WHERE session_id = ?",
+            params![prompts, session_id],
+        )?;
+        Ok(())
+    }
+
     pub fn get_transcript_path(&self, session_id: &str) -> Result<Option<String>> {
         self.conn
             .query_row(
@@ -228,6 +1195,232 @@ impl Database {
         Ok(())
     }
 
+    /// Stores a summary of the transcript as it was at `as_of`, unless the
+    /// plate already has one from a later point. Returns whether it was
+    /// stored. The history only gets a row when the text changes.
+    pub fn set_summary_if_newer(
+        &self,
+        session_id: &str,
+        summary: &str,
+        as_of: &str,
+    ) -> Result<bool> {
+        let changed: bool = self
+            .conn
+            .query_row(
+                "SELECT summary IS NOT ?1 FROM plates WHERE session_id = ?2",
+                params![summary, session_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(false);
+        let updated = self.conn.execute(
+            "UPDATE plates SET summary = ?1, summary_at = ?2
+             WHERE session_id = ?3 AND (summary_at IS NULL OR summary_at < ?2)",
+            params![summary, as_of, session_id],
+        )?;
+        if updated > 0 && changed {
+            self.conn.execute(
+                "INSERT INTO summaries (session_id, summary, created_at) VALUES (?, ?, ?)",
+                params![session_id, summary, as_of],
+            )?;
+        }
+        Ok(updated > 0)
+    }
+
+    /// Marks everything the plate did up to `now` as seen. Returns false
+    /// for an unknown plate.
+    pub fn acknowledge(&self, session_id: &str, now: &str) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE plates SET acknowledged_at = ? WHERE session_id = ?",
+            params![now, session_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Summaries, todo completions and status changes after `since`, or
+    /// after the plate was last acknowledged when `since` is `None`. Oldest
+    /// first; `None` for an unknown plate.
+    pub fn get_digest(&self, session_id: &str, since: Option<&str>) -> Result<Option<Digest>> {
+        let acknowledged_at: Option<Option<String>> = self
+            .conn
+            .query_row(
+                "SELECT acknowledged_at FROM plates WHERE session_id = ?",
+                [session_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(acknowledged_at) = acknowledged_at else {
+            return Ok(None);
+        };
+        let since = since.map(str::to_string).or(acknowledged_at);
+        let after = since.as_deref().unwrap_or("");
+
+        let mut entries = Vec::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT summary, created_at FROM summaries WHERE session_id = ? AND created_at > ?",
+        )?;
+        for row in stmt.query_map(params![session_id, after], |row| {
+            Ok(DigestEntry::Summary {
+                summary: row.get(0)?,
+                at: row.get(1)?,
+            })
+        })? {
+            entries.push(row?);
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT content, completed_at FROM todo_completions
+             WHERE session_id = ? AND completed_at > ?",
+        )?;
+        for row in stmt.query_map(params![session_id, after], |row| {
+            Ok(DigestEntry::TodoCompleted {
+                content: row.get(0)?,
+                at: row.get(1)?,
+            })
+        })? {
+            entries.push(row?);
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT from_status, to_status, created_at FROM transitions
+             WHERE session_id = ? AND created_at > ?",
+        )?;
+        for row in stmt.query_map(params![session_id, after], |row| {
+            Ok(DigestEntry::Status {
+                from: row.get(0)?,
+                to: row.get(1)?,
+                at: row.get(2)?,
+            })
+        })? {
+            entries.push(row?);
+        }
+
+        // Stable, so a status change and the summary it triggered keep order
+        entries.sort_by(|a, b| a.at().cmp(b.at()));
+        let skip = entries.len().saturating_sub(DIGEST_LIMIT);
+        entries.drain(..skip);
+        Ok(Some(Digest {
+            session_id: session_id.to_string(),
+            since,
+            entries,
+        }))
+    }
+
+    /// Stores the AskUserQuestion questions a plate waits on, as the JSON
+    /// array from the tool's input.
+    pub fn set_pending_questions(&self, session_id: &str, questions_json: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE plates SET pending_questions = ? WHERE session_id = ?",
+            params![questions_json, session_id],
+        )?;
+        Ok(())
+    }
+
+    /// Stores the plan a plate submitted with ExitPlanMode, replacing any
+    /// earlier one.
+    pub fn set_plan(&self, session_id: &str, plan: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE plates SET plan = ? WHERE session_id = ?",
+            params![plan, session_id],
+        )?;
+        Ok(())
+    }
+
+    /// Replaces a plate's usage with `usage` (model, UTC day and totals),
+    /// which covers its whole transcript. Writing the same totals again
+    /// changes nothing, however often the transcript is re-read.
+    pub fn set_usage(&self, session_id: &str, usage: &[(&str, &str, UsageTotals)]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM token_usage WHERE session_id = ?", [session_id])?;
+        for (model, day, totals) in usage {
+            let tokens = &totals.tokens;
+            tx.execute(
+                r#"INSERT INTO token_usage (session_id, model, day, input_tokens, output_tokens,
+                                            cache_creation_tokens, cache_read_tokens, cost_usd)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+                params![
+                    session_id,
+                    model,
+                    day,
+                    tokens.input_tokens as i64,
+                    tokens.output_tokens as i64,
+                    tokens.cache_creation_tokens as i64,
+                    tokens.cache_read_tokens as i64,
+                    totals.cost_usd
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// A plate's usage per model, most expensive first.
+    pub fn get_plate_usage(&self, session_id: &str) -> Result<Vec<ModelUsage>> {
+        let mut stmt = self.conn.prepare(&format!(
+            r#"SELECT model, {}
+               FROM token_usage WHERE session_id = ?
+               GROUP BY model
+               ORDER BY 6 DESC, model"#,
+            USAGE_SUMS
+        ))?;
+        let rows = stmt.query_map([session_id], |row| {
+            Ok(ModelUsage {
+                model: row.get(0)?,
+                totals: usage_totals(row, 1)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Usage per project and per UTC day, from `since` (`YYYY-MM-DD`) on.
+    pub fn usage_report(&self, since: Option<&str>) -> Result<UsageReport> {
+        let since_day = since.unwrap_or("");
+        let mut stmt = self.conn.prepare(&format!(
+            r#"SELECT p.project_path, {}
+               FROM token_usage
+               JOIN plates p ON p.session_id = token_usage.session_id
+               WHERE day >= ?
+               GROUP BY p.project_path
+               ORDER BY 6 DESC, p.project_path"#,
+            USAGE_SUMS
+        ))?;
+        let projects = stmt
+            .query_map([since_day], |row| {
+                Ok(ProjectUsage {
+                    project_path: row.get(0)?,
+                    totals: usage_totals(row, 1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            r#"SELECT day, {}
+               FROM token_usage
+               WHERE day >= ?
+               GROUP BY day
+               ORDER BY day"#,
+            USAGE_SUMS
+        ))?;
+        let days = stmt
+            .query_map([since_day], |row| {
+                Ok(DailyUsage {
+                    day: row.get(0)?,
+                    totals: usage_totals(row, 1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut total = UsageTotals::default();
+        for day in &days {
+            total.tokens.add(&day.totals.tokens);
+            total.cost_usd += day.totals.cost_usd;
+        }
+        Ok(UsageReport {
+            since: since.map(str::to_string),
+            total,
+            projects,
+            days,
+        })
+    }
+
     pub fn get_goal(&self, session_id: &str) -> Result<Option<String>> {
         self.conn
             .query_row(
@@ -286,21 +1479,596 @@ impl Database {
             .collect();
 
         for plate_id in &plate_ids {
-            self.conn.execute(
-                "UPDATE plates SET status = 'closed', updated_at = ? WHERE session_id = ?",
-                params![now, plate_id],
-            )?;
+            self.set_status(plate_id, "closed", now)?;
         }
         Ok(plate_ids)
     }
 
-    pub fn delete_plate(&self, session_id: &str) -> Result<()> {
+    /// Deletes a plate and everything recorded about it, remembering its
+    /// session id so `sp import-transcripts` doesn't bring it back.
+    pub fn delete_plate(&self, session_id: &str) -> Result<bool> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO dismissed_plates (session_id) VALUES (?)",
+            [session_id],
+        )?;
         self.conn
             .execute("DELETE FROM todos WHERE session_id = ?", [session_id])?;
         self.conn
             .execute("DELETE FROM events WHERE session_id = ?", [session_id])?;
         self.conn
-            .execute("DELETE FROM plates WHERE session_id = ?", [session_id])?;
-        Ok(())
+            .execute("DELETE FROM transitions WHERE session_id = ?", [session_id])?;
+        self.conn
+            .execute("DELETE FROM summaries WHERE session_id = ?", [session_id])?;
+        self.conn.execute(
+            "DELETE FROM todo_completions WHERE session_id = ?",
+            [session_id],
+        )?;
+        self.conn
+            .execute("DELETE FROM token_usage WHERE session_id = ?", [session_id])?;
+        self.conn
+            .execute("DELETE FROM plates WHERE session_id = ?", [session_id])
+            .map(|deleted| deleted > 0)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Database {
+        Database::open(Path::new(":memory:")).unwrap()
+    }
+
+    fn add_plate(db: &Database, session_id: &str, project_path: &str, branch: Option<&str>) {
+        db.upsert_plate(
+            session_id,
+            project_path,
+            None,
+            branch,
+            None,
+            "running",
+            "session_start",
+            None,
+            "2026-01-01T00:00:00+00:00",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn fts_query_quotes_and_prefixes_terms() {
+        assert_eq!(
+            fts_query("auth migr").as_deref(),
+            Some(r#""auth"* "migr"*"#)
+        );
+        assert_eq!(
+            fts_query(r#"fix "db"-layer"#).as_deref(),
+            Some(r#""fix"* "db-layer"*"#)
+        );
+        assert_eq!(fts_query("   "), None);
+    }
+
+    #[test]
+    fn since_accepts_dates_and_timestamps() {
+        assert_eq!(
+            parse_since("2026-01-05").unwrap(),
+            "2026-01-05T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_since("2026-01-05T10:00:00+02:00").unwrap(),
+            "2026-01-05T08:00:00+00:00"
+        );
+        assert!(parse_since("last week").is_err());
+    }
+
+    #[test]
+    fn query_plates_filters_and_pages() {
+        let db = test_db();
+        for (id, project, branch, minute) in [
+            ("a", "/work/api", "main", 1),
+            ("b", "/work/api", "feature", 2),
+            ("c", "/work/web", "main", 3),
+            ("d", "/work/api-old", "main", 4),
+        ] {
+            db.upsert_plate(
+                id,
+                project,
+                None,
+                Some(branch),
+                None,
+                "running",
+                "session_start",
+                None,
+                &format!("2026-01-01T00:0{}:00+00:00", minute),
+            )
+            .unwrap();
+        }
+        db.set_status("c", "idle", "2026-01-01T00:05:00+00:00")
+            .unwrap();
+
+        let ids = |query: &PlateQuery| -> Vec<String> {
+            db.query_plates(query)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.session_id)
+                .collect()
+        };
+        let all = PlateQuery {
+            limit: 10,
+            ..Default::default()
+        };
+
+        assert_eq!(ids(&all), vec!["c", "d", "b", "a"]);
+        assert_eq!(
+            ids(&PlateQuery {
+                project: Some("api".to_string()),
+                ..all.clone()
+            }),
+            vec!["b", "a"]
+        );
+        assert_eq!(
+            ids(&PlateQuery {
+                branch: Some("main".to_string()),
+                statuses: vec![crate::models::PlateStatus::Running],
+                ..all.clone()
+            }),
+            vec!["d", "a"]
+        );
+        assert_eq!(
+            ids(&PlateQuery {
+                since: Some("2026-01-01T00:03:00+00:00".to_string()),
+                ..all.clone()
+            }),
+            vec!["c", "d"]
+        );
+
+        let first_page = PlateQuery {
+            sort: PlateSort::Project,
+            ascending: true,
+            limit: 2,
+            ..Default::default()
+        };
+        let page = db.query_plates(&first_page).unwrap();
+        assert_eq!(
+            page.iter()
+                .map(|p| p.session_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        let last = page.last().unwrap();
+        let next = PlateQuery {
+            after: Some((PlateSort::Project.key(last), last.session_id.clone())),
+            ..first_page
+        };
+        assert_eq!(ids(&next), vec!["d", "c"]);
+    }
+
+    #[test]
+    fn search_matches_summary_goal_and_branch() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", Some("auth-migration"));
+        add_plate(&db, "b", "/work/web", Some("main"));
+        db.set_summary("b", "Dark mode: styling buttons").unwrap();
+        db.set_goal("b", "Dark mode").unwrap();
+
+        let ids = |q: &str| -> Vec<String> {
+            db.search_plates(q)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.session_id)
+                .collect()
+        };
+        assert_eq!(ids("auth"), vec!["a"]);
+        assert_eq!(ids("dark butt"), vec!["b"]);
+        assert_eq!(ids("web"), vec!["b"]);
+        assert!(ids("nothing").is_empty());
+    }
+
+    #[test]
+    fn status_changes_are_recorded_as_transitions() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", None);
+        db.upsert_plate(
+            "a",
+            "/work/api",
+            None,
+            None,
+            None,
+            "running",
+            "tool_call",
+            Some("Bash"),
+            "2026-01-01T00:00:01+00:00",
+        )
+        .unwrap();
+        db.set_status("a", "idle", "2026-01-01T00:00:02+00:00")
+            .unwrap();
+        db.mark_stopped("/work/api", "2026-01-01T00:00:03+00:00")
+            .unwrap();
+
+        let steps: Vec<(Option<String>, String)> = db
+            .get_transitions()
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.from_status, t.to_status))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (None, "running".to_string()),
+                (Some("running".to_string()), "idle".to_string()),
+                (Some("idle".to_string()), "closed".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            db.count_plates_by_status().unwrap(),
+            vec![("closed".to_string(), 1)]
+        );
+        assert!(db.size_bytes().unwrap() > 0);
+
+        let first = db.get_transitions().unwrap()[0].id;
+        let later = db.get_transitions_after(first, 1).unwrap();
+        assert_eq!(later.len(), 1);
+        assert_eq!(later[0].to_status, "idle");
+        assert_eq!(db.latest_transition_id().unwrap(), first + 2);
+    }
+
+    #[test]
+    fn import_plate_skips_known_sessions() {
+        let db = test_db();
+        add_plate(&db, "live", "/work/api", None);
+        let import = |id: &str| {
+            db.import_plate(
+                id,
+                "/work/api",
+                "/tmp/t.jsonl",
+                Some("main"),
+                None,
+                "2026-01-01T00:00:00+00:00",
+                "2026-01-01T01:00:00+00:00",
+            )
+            .unwrap()
+        };
+        assert!(!import("live"));
+        assert!(import("old"));
+        assert!(!import("old"));
+
+        let plates = db.get_plates().unwrap();
+        assert_eq!(plates.len(), 2);
+        let old = plates.iter().find(|p| p.session_id == "old").unwrap();
+        assert_eq!(old.status, crate::models::PlateStatus::Closed);
+        assert_eq!(db.get_status("live").unwrap().as_deref(), Some("running"));
+    }
+
+    #[test]
+    fn deleted_plates_are_remembered_as_dismissed() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", None);
+        assert!(!db.is_dismissed("a").unwrap());
+        assert!(db.delete_plate("a").unwrap());
+        assert!(db.is_dismissed("a").unwrap());
+        assert!(!db.is_dismissed("b").unwrap());
+    }
+
+    #[test]
+    fn search_includes_prompts_and_forgets_deleted_plates() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", None);
+        db.set_search_prompts("a", "please fix the flaky websocket test")
+            .unwrap();
+        assert_eq!(db.search_plates("flaky").unwrap().len(), 1);
+
+        db.delete_plate("a").unwrap();
+        assert!(db.search_plates("flaky").unwrap().is_empty());
+    }
+
+    #[test]
+    fn plate_detail_includes_todos_goal_and_recent_events() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", Some("main"));
+        db.set_goal("a", "Fix auth").unwrap();
+        db.upsert_todos(
+            "a",
+            r#"[{"content":"Write test","status":"in_progress","activeForm":"Writing test"},{"status":"pending"}]"#,
+            "2026-01-01T00:01:00+00:00",
+        )
+        .unwrap();
+        for (event_type, tool) in [("tool_start", "Read"), ("tool_call", "Read")] {
+            let payload = serde_json::json!({
+                "event_type": event_type,
+                "tool_name": tool,
+                "tool_params": {"file_path": "/work/api/auth.rs"},
+            });
+            db.insert_event(
+                "a",
+                event_type,
+                &payload.to_string(),
+                "2026-01-01T00:02:00+00:00",
+            )
+            .unwrap();
+        }
+
+        let detail = db.get_plate_detail("a").unwrap().unwrap();
+        assert_eq!(detail.goal.as_deref(), Some("Fix auth"));
+        assert_eq!(detail.todos.len(), 1);
+        assert_eq!(detail.todos[0].active_form.as_deref(), Some("Writing test"));
+        assert_eq!(
+            detail.todos_updated_at.as_deref(),
+            Some("2026-01-01T00:01:00+00:00")
+        );
+        assert_eq!(detail.recent_events.len(), 2);
+        assert_eq!(detail.recent_events[0].event_type, "tool_call");
+        assert_eq!(detail.recent_tool_calls.len(), 1);
+        assert_eq!(
+            detail.recent_tool_calls[0].tool_name.as_deref(),
+            Some("Read")
+        );
+        assert!(db.get_plate_detail("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn inbox_items_snooze_and_wake() {
+        let db = test_db();
+        let context = serde_json::json!({"pr": 42});
+        let item = db
+            .insert_inbox_item(
+                "review",
+                "Changes requested on #42",
+                Some("please add tests"),
+                Some(&context),
+                "2026-01-01T00:00:00+00:00",
+            )
+            .unwrap();
+        assert_eq!(item.status, InboxStatus::Pending);
+        assert_eq!(item.context.unwrap()["pr"], 42);
+
+        db.update_inbox_item(
+            item.id,
+            InboxStatus::Snoozed,
+            Some("2026-01-01T01:00:00+00:00"),
+            None,
+            "2026-01-01T00:00:00+00:00",
+        )
+        .unwrap();
+        let pending = |now: &str| db.list_inbox(&[InboxStatus::Pending], now).unwrap().len();
+        assert_eq!(pending("2026-01-01T00:30:00+00:00"), 0);
+        assert_eq!(pending("2026-01-01T01:00:00+00:00"), 1);
+
+        assert!(db
+            .update_inbox_item(
+                item.id,
+                InboxStatus::Dispatched,
+                None,
+                Some("abc"),
+                "2026-01-01T02:00:00+00:00",
+            )
+            .unwrap());
+        let item = db.get_inbox_item(item.id).unwrap().unwrap();
+        assert_eq!(item.dispatched_to.as_deref(), Some("abc"));
+        assert!(!db
+            .update_inbox_item(
+                999,
+                InboxStatus::Dismissed,
+                None,
+                None,
+                "2026-01-01T02:00:00+00:00"
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn digest_lists_changes_since_acknowledgement() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", None);
+        let todos = |first: &str, second: &str| {
+            serde_json::json!([
+                {"content": "Write tests", "status": first},
+                {"content": "Fix bug", "status": second},
+            ])
+            .to_string()
+        };
+        db.upsert_todos(
+            "a",
+            &todos("completed", "pending"),
+            "2026-01-01T00:00:01+00:00",
+        )
+        .unwrap();
+        db.acknowledge("a", "2026-01-01T00:00:02+00:00").unwrap();
+
+        db.set_summary_if_newer("a", "Auth: fixing bug", "2026-01-01T00:00:03+00:00")
+            .unwrap();
+        db.upsert_todos(
+            "a",
+            &todos("completed", "completed"),
+            "2026-01-01T00:00:04+00:00",
+        )
+        .unwrap();
+        db.set_status("a", "idle", "2026-01-01T00:00:05+00:00")
+            .unwrap();
+
+        let digest = db.get_digest("a", None).unwrap().unwrap();
+        assert_eq!(digest.since.as_deref(), Some("2026-01-01T00:00:02+00:00"));
+        assert_eq!(
+            digest.entries,
+            vec![
+                DigestEntry::Summary {
+                    summary: "Auth: fixing bug".to_string(),
+                    at: "2026-01-01T00:00:03+00:00".to_string(),
+                },
+                DigestEntry::TodoCompleted {
+                    content: "Fix bug".to_string(),
+                    at: "2026-01-01T00:00:04+00:00".to_string(),
+                },
+                DigestEntry::Status {
+                    from: Some("running".to_string()),
+                    to: "idle".to_string(),
+                    at: "2026-01-01T00:00:05+00:00".to_string(),
+                },
+            ]
+        );
+
+        // An explicit start overrides the acknowledgement
+        let all = db.get_digest("a", Some("")).unwrap().unwrap();
+        assert_eq!(all.entries.len(), 5);
+
+        db.acknowledge("a", "2026-01-01T00:00:06+00:00").unwrap();
+        assert!(db
+            .get_digest("a", None)
+            .unwrap()
+            .unwrap()
+            .entries
+            .is_empty());
+        assert!(db.get_digest("missing", None).unwrap().is_none());
+    }
+
+    #[test]
+    fn pending_questions_clear_when_the_plate_stops_waiting() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", None);
+        db.set_status("a", "awaiting_input", "2026-01-01T00:00:01+00:00")
+            .unwrap();
+        db.set_pending_questions(
+            "a",
+            r#"[{"question":"Which DB?","header":"DB","multiSelect":false,
+                 "options":[{"label":"Postgres","description":"Shared"},{"label":"SQLite"}]}]"#,
+        )
+        .unwrap();
+        let plate = db.get_plate("a").unwrap().unwrap();
+        assert_eq!(
+            plate.pending_questions[0].to_string(),
+            "Which DB? [1] Postgres [2] SQLite"
+        );
+
+        // Another event while still waiting keeps the question
+        db.set_status("a", "awaiting_input", "2026-01-01T00:00:02+00:00")
+            .unwrap();
+        assert_eq!(
+            db.get_plate("a").unwrap().unwrap().pending_questions.len(),
+            1
+        );
+
+        db.set_status("a", "running", "2026-01-01T00:00:03+00:00")
+            .unwrap();
+        assert!(db
+            .get_plate("a")
+            .unwrap()
+            .unwrap()
+            .pending_questions
+            .is_empty());
+    }
+
+    #[test]
+    fn plans_are_kept_on_the_plate_detail() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", None);
+        assert!(!db.get_plate("a").unwrap().unwrap().has_plan);
+
+        db.set_plan("a", "# Plan\n1. First").unwrap();
+        db.set_plan("a", "# Plan\n1. Second").unwrap();
+        db.set_status("a", "running", "2026-01-01T00:00:01+00:00")
+            .unwrap();
+        let detail = db.get_plate_detail("a").unwrap().unwrap();
+        assert!(detail.plate.has_plan);
+        assert_eq!(
+            detail.status_since.as_deref(),
+            Some("2026-01-01T00:00:00+00:00")
+        );
+        assert_eq!(detail.plan.as_deref(), Some("# Plan\n1. Second"));
+    }
+
+    #[test]
+    fn usage_adds_up_per_plate_project_and_day() {
+        let db = test_db();
+        add_plate(&db, "a", "/work/api", None);
+        add_plate(&db, "b", "/work/api", Some("feature"));
+        add_plate(&db, "c", "/work/web", None);
+        let totals = |input: u64, output: u64, cost_usd: f64| UsageTotals {
+            tokens: TokenUsage {
+                input_tokens: input,
+                output_tokens: output,
+                ..Default::default()
+            },
+            cost_usd,
+        };
+        // The first write is replaced, not added to
+        db.set_usage("a", &[("claude-opus-4-1", "2026-01-01", totals(5, 5, 9.0))])
+            .unwrap();
+        db.set_usage(
+            "a",
+            &[
+                ("claude-sonnet-4-5", "2026-01-01", totals(200, 20, 1.0)),
+                ("claude-haiku-4-5", "2026-01-02", totals(50, 10, 0.1)),
+            ],
+        )
+        .unwrap();
+        db.set_usage(
+            "b",
+            &[("claude-sonnet-4-5", "2026-01-02", totals(20, 10, 0.2))],
+        )
+        .unwrap();
+        db.set_usage(
+            "c",
+            &[("claude-opus-4-1", "2026-01-02", totals(10, 10, 2.0))],
+        )
+        .unwrap();
+
+        let plate = db.get_plate("a").unwrap().unwrap();
+        assert_eq!(plate.tokens, 280);
+        assert!((plate.cost_usd - 1.1).abs() < 1e-9);
+        let detail = db.get_plate_detail("a").unwrap().unwrap();
+        assert_eq!(detail.usage[0].model, "claude-sonnet-4-5");
+        assert_eq!(detail.usage[0].totals.tokens.input_tokens, 200);
+        assert_eq!(detail.plate.tokens, 280);
+
+        let report = db.usage_report(None).unwrap();
+        assert_eq!(report.projects[0].project_path, "/work/web");
+        assert_eq!(report.projects[1].totals.tokens.input_tokens, 270);
+        assert_eq!(report.days.len(), 2);
+        assert!((report.total.cost_usd - 3.3).abs() < 1e-9);
+
+        let report = db.usage_report(Some("2026-01-02")).unwrap();
+        assert_eq!(report.days.len(), 1);
+        assert_eq!(report.total.tokens.input_tokens, 80);
+
+        db.delete_plate("a").unwrap();
+        assert_eq!(db.usage_report(None).unwrap().days[0].day, "2026-01-02");
+    }
+
+    #[test]
+    fn older_summaries_do_not_overwrite_newer_ones() {
+        let db = test_db();
+        db.upsert_plate(
+            "a",
+            "/work/api",
+            None,
+            None,
+            None,
+            "running",
+            "tool_call",
+            None,
+            "2026-01-01T00:00:00+00:00",
+        )
+        .unwrap();
+
+        assert!(db
+            .set_summary_if_newer("a", "Auth: writing tests", "2026-01-01T00:00:10+00:00")
+            .unwrap());
+        assert!(!db
+            .set_summary_if_newer("a", "Auth: reading code", "2026-01-01T00:00:05+00:00")
+            .unwrap());
+        assert_eq!(
+            db.get_summary("a").unwrap().as_deref(),
+            Some("Auth: writing tests")
+        );
+
+        // The same text again moves the plate on without a second history row
+        assert!(db
+            .set_summary_if_newer("a", "Auth: writing tests", "2026-01-01T00:00:20+00:00")
+            .unwrap());
+        let history: i64 = db
+            .conn()
+            .query_row("SELECT COUNT(*) FROM summaries", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(history, 1);
     }
 }
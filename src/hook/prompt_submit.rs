@@ -5,7 +5,7 @@ use super::{check_daemon_health, post_event, read_stdin_json};
 pub async fn prompt_submit() -> Result<()> {
     let data = read_stdin_json()?;
 
-    let client = reqwest::Client::new();
+    let client = crate::api_token::client();
     if !check_daemon_health(&client).await {
         return Ok(());
     }
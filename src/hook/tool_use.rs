@@ -13,7 +13,7 @@ pub async fn post_tool_use() -> Result<()> {
 async fn tool_event(event_type: &str) -> Result<()> {
     let data = read_stdin_json()?;
 
-    let client = reqwest::Client::new();
+    let client = crate::api_token::client();
     if !check_daemon_health(&client).await {
         return Ok(());
     }
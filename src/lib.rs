@@ -1,3 +1,5 @@
+pub mod api_token;
+pub mod backup;
 pub mod cli;
 pub mod config;
 pub mod daemon;
@@ -6,6 +8,8 @@ pub mod hook;
 pub mod models;
 pub mod recovery;
 pub mod state_machine;
+#[cfg(test)]
+pub mod test_support;
 pub mod tui;
 
 use std::process::Command;
@@ -19,8 +23,8 @@ pub fn build_version() -> String {
     }
 }
 
-fn kill_daemon() {
-    let client = reqwest::blocking::Client::new();
+pub fn kill_daemon() {
+    let client = api_token::blocking_client();
     let _ = client
         .post(format!("{}/shutdown", hook::DAEMON_URL))
         .timeout(std::time::Duration::from_secs(1))
@@ -40,7 +44,7 @@ fn spawn_daemon() {
 }
 
 pub fn ensure_daemon_running() {
-    let client = reqwest::blocking::Client::new();
+    let client = api_token::blocking_client();
     let my_version = build_version();
 
     let response = client
@@ -3,9 +3,8 @@ use std::os::unix::process::CommandExt;
 use std::process::Command;
 use std::sync::Arc;
 
-use plate_spinner::config::get_data_dir;
+use plate_spinner::backup::{backup_dir, db_path, open_with_recovery, StartupCheck};
 use plate_spinner::daemon::state::AppState;
-use plate_spinner::db::Database;
 use plate_spinner::ensure_daemon_running;
 
 #[derive(Parser)]
@@ -30,6 +29,50 @@ enum Commands {
     },
     #[command(about = "List plates as JSON")]
     Plates,
+    #[command(about = "Search plates by goal, summary, project, branch or prompt")]
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+        #[arg(long, help = "Print results as JSON")]
+        json: bool,
+    },
+    #[command(about = "Show what a plate did since you last caught up on it")]
+    Catchup {
+        #[arg(help = "Session id (or a prefix), or project name with optional /branch")]
+        plate: String,
+        #[arg(long, help = "Print the digest as JSON")]
+        json: bool,
+        #[arg(long, help = "Don't mark the plate as caught up")]
+        keep: bool,
+    },
+    #[command(about = "Export plates, transitions and events as JSONL or CSV")]
+    Export {
+        #[arg(long, default_value = "jsonl", help = "Output format: jsonl or csv")]
+        format: String,
+        #[arg(long, help = "Only export one table: plates, transitions or events")]
+        table: Option<String>,
+        #[arg(
+            long,
+            help = "Only include activity at or after this date (YYYY-MM-DD or RFC 3339)"
+        )]
+        since: Option<String>,
+        #[arg(long, help = "Only include plates for this project name or path")]
+        project: Option<String>,
+        #[arg(
+            long,
+            help = "Only include plates with these statuses (comma-separated)"
+        )]
+        status: Option<String>,
+        #[arg(long, short, help = "Write one file per table into this directory")]
+        output: Option<String>,
+    },
+    #[command(about = "Import past sessions from Claude transcripts as closed plates")]
+    ImportTranscripts {
+        #[arg(long, help = "Transcript root (defaults to ~/.claude/projects)")]
+        dir: Option<String>,
+        #[arg(long, help = "Generate summaries with the summarizer (uses the API)")]
+        summarize: bool,
+    },
     #[command(about = "Install hooks")]
     Install,
     #[command(about = "Stop the daemon")]
@@ -39,6 +82,11 @@ enum Commands {
         #[command(subcommand)]
         command: Option<ConfigCommands>,
     },
+    #[command(about = "Back up or restore the plate database")]
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
     #[command(about = "Manage API key authentication")]
     Auth {
         #[command(subcommand)]
@@ -65,6 +113,17 @@ enum ConfigCommands {
     Import { file: String },
 }
 
+#[derive(Subcommand)]
+enum DbCommands {
+    #[command(about = "Write a backup (to the backups directory unless --output is given)")]
+    Backup {
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    #[command(about = "Stop the daemon and restore a backup (the newest if no file is given)")]
+    Restore { file: Option<String> },
+}
+
 #[derive(Subcommand)]
 enum AuthCommands {
     #[command(about = "Set API key")]
@@ -95,9 +154,16 @@ fn main() {
         Some(Commands::Daemon) => {
             let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
             rt.block_on(async {
-                let db_path = get_data_dir().join("state.db");
-                let db = Database::open(&db_path).expect("Failed to open database");
-                let state = Arc::new(AppState::new(db));
+                let (db, db_health) =
+                    open_with_recovery(&db_path(), &backup_dir()).expect("Failed to open database");
+                if db_health.startup != StartupCheck::Ok {
+                    eprintln!(
+                        "Database failed its integrity check ({:?}); quarantined to {}",
+                        db_health.startup,
+                        db_health.quarantined.as_deref().unwrap_or("?")
+                    );
+                }
+                let state = Arc::new(AppState::with_db_health(db, db_health));
                 if let Err(e) = plate_spinner::daemon::run(state, 7890).await {
                     eprintln!("Daemon error: {}", e);
                 }
@@ -116,6 +182,52 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Some(Commands::Search { query, json }) => {
+            if let Err(e) = plate_spinner::cli::search::search(&query.join(" "), json) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Catchup { plate, json, keep }) => {
+            if let Err(e) = plate_spinner::cli::catchup::catchup(&plate, json, keep) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Export {
+            format,
+            table,
+            since,
+            project,
+            status,
+            output,
+        }) => {
+            let result = plate_spinner::cli::export::ExportFilter::new(
+                since.as_deref(),
+                project.as_deref(),
+                status.as_deref(),
+            )
+            .and_then(|filter| {
+                plate_spinner::cli::export::export(
+                    &format,
+                    table.as_deref(),
+                    output.as_deref(),
+                    filter,
+                )
+            });
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::ImportTranscripts { dir, summarize }) => {
+            if let Err(e) =
+                plate_spinner::cli::import::import_transcripts(dir.as_deref(), summarize)
+            {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
         Some(Commands::Install) => {
             if let Err(e) = plate_spinner::cli::install::install() {
                 eprintln!("Error: {}", e);
@@ -145,6 +257,18 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Some(Commands::Db { command }) => {
+            let result = match command {
+                DbCommands::Backup { output } => {
+                    plate_spinner::cli::db::db_backup(output.as_deref())
+                }
+                DbCommands::Restore { file } => plate_spinner::cli::db::db_restore(file.as_deref()),
+            };
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
         Some(Commands::Auth { command }) => {
             let result = match command {
                 Some(AuthCommands::Set) => plate_spinner::cli::auth::auth_set(),
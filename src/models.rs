@@ -14,6 +14,16 @@ pub enum PlateStatus {
 }
 
 impl PlateStatus {
+    pub const ALL: [PlateStatus; 7] = [
+        Self::Starting,
+        Self::Running,
+        Self::Idle,
+        Self::AwaitingInput,
+        Self::AwaitingApproval,
+        Self::Error,
+        Self::Closed,
+    ];
+
     pub fn from_tool(tool_name: &str) -> Self {
         match tool_name {
             "AskUserQuestion" => Self::AwaitingInput,
@@ -121,10 +131,298 @@ pub struct Plate {
     pub summary: Option<String>,
     #[serde(default)]
     pub todo_progress: Option<String>,
+    /// Tokens used so far, cache reads and writes included.
+    #[serde(default)]
+    pub tokens: u64,
+    /// Estimated cost in USD, from the `[prices]` table.
+    #[serde(default)]
+    pub cost_usd: f64,
+    /// What an `awaiting_input` plate asked with AskUserQuestion; empty in
+    /// any other state.
+    #[serde(default)]
+    pub pending_questions: Vec<Question>,
+    /// Whether the plate has submitted a plan with ExitPlanMode; the plan
+    /// itself is in the plate detail.
+    #[serde(default)]
+    pub has_plan: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Name of the remote daemon this plate comes from; `None` when local.
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Set on remote plates whose daemon doesn't accept actions from here.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxStatus {
+    #[default]
+    Pending,
+    Dispatched,
+    Dismissed,
+    Snoozed,
+}
+
+impl InboxStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Dispatched => "dispatched",
+            Self::Dismissed => "dismissed",
+            Self::Snoozed => "snoozed",
+        }
+    }
+}
+
+impl std::str::FromStr for InboxStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "dispatched" => Ok(Self::Dispatched),
+            "dismissed" => Ok(Self::Dismissed),
+            "snoozed" => Ok(Self::Snoozed),
+            _ => Err(()),
+        }
+    }
+}
+
+/// An externally submitted work item waiting to be dispatched to a plate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxItem {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub context: Option<serde_json::Value>,
+    pub status: InboxStatus,
+    #[serde(default)]
+    pub snoozed_until: Option<String>,
+    #[serde(default)]
+    pub dispatched_to: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// A question asked with AskUserQuestion, as Claude sends it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Question {
+    pub question: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(default)]
+    pub options: Vec<QuestionOption>,
+    #[serde(rename = "multiSelect", default)]
+    pub multi_select: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestionOption {
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl std::fmt::Display for Question {
+    /// "Which DB? [1] Postgres [2] SQLite"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.question)?;
+        for (i, option) in self.options.iter().enumerate() {
+            write!(f, " [{}] {}", i + 1, option.label)?;
+        }
+        Ok(())
+    }
+}
+
+/// One entry of a TodoWrite list, as Claude sends it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Todo {
+    pub content: String,
+    pub status: String,
+    #[serde(
+        rename = "activeForm",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub active_form: Option<String>,
+}
+
+/// Something a plate did, as listed when catching up on it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DigestEntry {
+    Summary {
+        summary: String,
+        at: String,
+    },
+    TodoCompleted {
+        content: String,
+        at: String,
+    },
+    Status {
+        from: Option<String>,
+        to: String,
+        at: String,
+    },
+}
+
+impl DigestEntry {
+    pub fn at(&self) -> &str {
+        match self {
+            DigestEntry::Summary { at, .. }
+            | DigestEntry::TodoCompleted { at, .. }
+            | DigestEntry::Status { at, .. } => at,
+        }
+    }
+}
+
+impl std::fmt::Display for DigestEntry {
+    /// "01-02 10:00  idle -> running"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}  ", local_time(self.at()))?;
+        match self {
+            DigestEntry::Summary { summary, .. } => write!(f, "{}", summary),
+            DigestEntry::TodoCompleted { content, .. } => write!(f, "done: {}", content),
+            DigestEntry::Status {
+                from: Some(from),
+                to,
+                ..
+            } => write!(f, "{} -> {}", from, to),
+            DigestEntry::Status { from: None, to, .. } => write!(f, "{}", to),
+        }
+    }
+}
+
+/// An RFC 3339 timestamp as local "MM-DD HH:MM", or unchanged if it doesn't
+/// parse.
+pub fn local_time(at: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(at)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| at.to_string())
+}
+
+/// What a plate did since it was last acknowledged, returned by
+/// `GET /plates/:session_id/digest`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Digest {
+    pub session_id: String,
+    /// When the plate was last acknowledged; `None` if it never was.
+    pub since: Option<String>,
+    pub entries: Vec<DigestEntry>,
+}
+
+/// Token counts from the `usage` blocks of assistant transcript entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+    }
+}
+
+/// Tokens and their estimated cost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    #[serde(flatten)]
+    pub tokens: TokenUsage,
+    pub cost_usd: f64,
+}
+
+/// A plate's usage of one model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub model: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectUsage {
+    pub project_path: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    /// UTC date, `YYYY-MM-DD`.
+    pub day: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Usage across all plates, returned by `GET /usage`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// First day counted; `None` for all time.
+    pub since: Option<String>,
+    pub total: UsageTotals,
+    pub projects: Vec<ProjectUsage>,
+    pub days: Vec<DailyUsage>,
+}
+
+/// A hook event recorded for a plate, with the payload unpacked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlateEvent {
+    pub id: i64,
+    pub event_type: String,
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    #[serde(default)]
+    pub tool_params: Option<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+/// Everything known about a single plate, returned by `GET /plates/:session_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlateDetail {
+    #[serde(flatten)]
+    pub plate: Plate,
+    #[serde(default)]
+    pub goal: Option<String>,
+    #[serde(default)]
+    pub todos: Vec<Todo>,
+    #[serde(default)]
+    pub todos_updated_at: Option<String>,
+    #[serde(default)]
+    pub recent_events: Vec<PlateEvent>,
+    #[serde(default)]
+    pub recent_tool_calls: Vec<PlateEvent>,
+    #[serde(default)]
+    pub usage: Vec<ModelUsage>,
+    /// The latest plan submitted with ExitPlanMode, as markdown.
+    #[serde(default)]
+    pub plan: Option<String>,
+    /// When the plate entered its current status.
+    #[serde(default)]
+    pub status_since: Option<String>,
+}
+
 impl Plate {
     pub fn project_name(&self) -> &str {
         self.project_path
@@ -133,4 +431,43 @@ impl Plate {
             .next()
             .unwrap_or(&self.project_path)
     }
+
+    /// Whether this plate belongs to `project`, given as a directory name
+    /// (`api`) or a full path (`/work/api`).
+    pub fn in_project(&self, project: &str) -> bool {
+        let project = project.trim_end_matches('/');
+        self.project_path.trim_end_matches('/') == project || self.project_name() == project
+    }
+
+    /// Completed and total todos, read from `todo_progress`.
+    pub fn todo_counts(&self) -> Option<(u32, u32)> {
+        let (done, total) = self.todo_progress.as_deref()?.split_once('/')?;
+        Some((done.parse().ok()?, total.parse().ok()?))
+    }
+
+    /// A plate with only the given fields set, for tests to adjust with
+    /// struct update syntax.
+    #[cfg(test)]
+    pub fn for_test(session_id: &str, project_path: &str, status: PlateStatus) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            project_path: project_path.to_string(),
+            transcript_path: None,
+            git_branch: None,
+            tmux_target: None,
+            status,
+            last_event_type: None,
+            last_tool: None,
+            summary: None,
+            todo_progress: None,
+            tokens: 0,
+            cost_usd: 0.0,
+            pending_questions: Vec::new(),
+            has_plan: false,
+            created_at: String::new(),
+            updated_at: String::new(),
+            host: None,
+            read_only: false,
+        }
+    }
 }
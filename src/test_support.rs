@@ -0,0 +1,80 @@
+//! Temporary files and directories for tests, removed when dropped.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A path under the system temp directory no other test in this process
+/// uses.
+fn unique_path(label: &str) -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "sp-{}-{}-{}",
+        label,
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+pub struct TempFile(pub PathBuf);
+
+impl TempFile {
+    /// An empty file.
+    pub fn new(label: &str) -> Self {
+        let path = unique_path(label);
+        std::fs::File::create(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+
+    /// Appends `text` as is, so a test can leave a line unfinished.
+    pub fn append(&self, text: &str) {
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&self.0)
+            .unwrap();
+        f.write_all(text.as_bytes()).unwrap();
+    }
+
+    /// Appends each of `lines` with a newline.
+    pub fn append_lines(&self, lines: &[&str]) {
+        for line in lines {
+            self.append(&format!("{}\n", line));
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+pub struct TempDir(pub PathBuf);
+
+impl TempDir {
+    pub fn new(label: &str) -> Self {
+        let path = unique_path(label);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Writes `lines` to `rel` under the directory, creating any parent
+    /// directories, and returns the file's path.
+    pub fn write(&self, rel: &str, lines: &[&str]) -> PathBuf {
+        let path = self.0.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let text: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -1,33 +1,62 @@
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
-use futures_util::StreamExt;
+use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
 
 use crate::config::{load_config, play_sound, save_config};
+use crate::daemon::summarizer::health::SummarizerHealth;
+use crate::daemon::websocket::{ClientMessage, Received, SeqTracker, ServerMessage};
 use crate::hook::DAEMON_URL;
-use crate::models::{Plate, PlateStatus};
+use crate::models::{Digest, InboxItem, Plate, PlateDetail, PlateStatus};
 
-use super::state::App;
+use super::state::{App, PlanView, FILTER_ROWS};
+use super::transcript::{TranscriptView, POLL_MILLIS};
 use super::ui::{next_sound, next_theme, prev_sound, render};
 
+const SNOOZE_SECS: i64 = 60 * 60;
+const STATUS_POLL_SECS: u64 = 15;
+/// Lines moved by page up/down in the plan overlay.
+const PLAN_PAGE: usize = 10;
+const DIGEST_TIMEOUT_SECS: u64 = 2;
+const DIGEST_RETRY_SECS: u64 = 5;
+
 pub async fn run() -> Result<Option<(String, String)>> {
     let config = load_config();
     let mut app = App::new(config);
 
     let mut terminal = ratatui::init();
 
-    let (tx, mut rx) = mpsc::channel::<()>(16);
+    let (tx, mut rx) = mpsc::channel::<ServerMessage>(256);
 
     tokio::spawn(connect_websocket(tx));
 
     refresh(&mut app).await;
+    let mut status_checked = std::time::Instant::now();
 
     loop {
+        if status_checked.elapsed().as_secs() >= STATUS_POLL_SECS {
+            refresh_status(&mut app).await;
+            status_checked = std::time::Instant::now();
+        }
+        load_digest(&mut app).await;
+        load_detail(&mut app).await;
+        if let Some(view) = &mut app.transcript_view {
+            if view.last_polled.elapsed().as_millis() >= POLL_MILLIS {
+                let _ = view.poll();
+            }
+        }
         terminal.draw(|f| render(f, &app))?;
 
         tokio::select! {
-            _ = rx.recv() => {
-                refresh(&mut app).await;
+            Some(msg) = rx.recv() => {
+                if let ServerMessage::InboxChanged { .. } = msg {
+                    refresh_inbox(&mut app).await;
+                    continue;
+                }
+                let mut plates = app.plates.clone();
+                msg.apply(&mut plates);
+                apply_plates(&mut app, plates).await;
             }
             _ = tokio::time::sleep(std::time::Duration::from_millis(50)) => {
                 if event::poll(std::time::Duration::ZERO)? {
@@ -51,24 +80,81 @@ pub async fn run() -> Result<Option<(String, String)>> {
 }
 
 async fn handle_key(app: &mut App, key: KeyCode) {
+    if app.transcript_view.is_some() {
+        handle_transcript_key(app, key);
+        return;
+    }
+    if let Some(view) = &mut app.plan_view {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('p') => app.plan_view = None,
+            KeyCode::Up | KeyCode::Char('k') => view.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => view.scroll_by(1),
+            KeyCode::PageUp => view.scroll_by(-(PLAN_PAGE as isize)),
+            KeyCode::PageDown | KeyCode::Char(' ') => view.scroll_by(PLAN_PAGE as isize),
+            KeyCode::Home | KeyCode::Char('g') => view.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => view.scroll = view.max_scroll.get(),
+            _ => {}
+        }
+        return;
+    }
     if app.show_sound_settings {
         handle_settings_key(app, key).await;
         return;
     }
+    if app.show_filters {
+        handle_filter_key(app, key);
+        return;
+    }
+    if app.search_editing {
+        handle_search_key(app, key).await;
+        return;
+    }
+    app.status_message = None;
+    if app.inbox_focused {
+        handle_inbox_key(app, key).await;
+        return;
+    }
+    if app.dispatching.is_some() {
+        match key {
+            KeyCode::Esc => {
+                app.dispatching = None;
+                return;
+            }
+            KeyCode::Enter => {
+                dispatch(app).await;
+                return;
+            }
+            _ => {}
+        }
+    }
 
     match key {
+        KeyCode::Esc if app.search_query.is_some() => app.clear_search(),
         KeyCode::Esc => app.should_quit = true,
+        KeyCode::Char('/') => app.start_search(),
         KeyCode::Char('r') => refresh(app).await,
+        KeyCode::Tab => app.toggle_inbox_focus(),
         KeyCode::Char('s') => {
             app.show_sound_settings = true;
             app.sound_settings_row = 0;
         }
         KeyCode::Char('c') => app.toggle_closed(),
-        KeyCode::Char('d') => {
-            if app.show_auth_banner {
-                app.dismiss_auth_banner();
-            }
+        KeyCode::Char('o') => {
+            app.cycle_sort();
+            let _ = save_config(&app.config);
+        }
+        KeyCode::Char('f') => {
+            app.show_filters = true;
+            app.filter_row = 0;
         }
+        KeyCode::Char('F') if !app.config.dashboard.filter.is_empty() => {
+            app.clear_filter();
+            let _ = save_config(&app.config);
+        }
+        KeyCode::Char('p') => open_plan(app).await,
+        KeyCode::Char('i') => app.show_detail = !app.show_detail,
+        KeyCode::Char('t') => open_transcript(app),
+        KeyCode::Char('d') if app.show_auth_banner => app.dismiss_auth_banner(),
         KeyCode::Up => {
             app.mark_seen();
             app.move_up();
@@ -81,6 +167,7 @@ async fn handle_key(app: &mut App, key: KeyCode) {
         }
         KeyCode::Enter => {
             app.mark_seen();
+            acknowledge(app).await;
             app.select();
         }
         KeyCode::Delete | KeyCode::Backspace => dismiss(app).await,
@@ -96,19 +183,119 @@ async fn handle_key(app: &mut App, key: KeyCode) {
     }
 }
 
-async fn handle_settings_key(app: &mut App, key: KeyCode) {
+fn handle_transcript_key(app: &mut App, key: KeyCode) {
+    let Some(view) = &mut app.transcript_view else {
+        return;
+    };
+    if view.search_editing {
+        match key {
+            KeyCode::Esc => {
+                view.search = None;
+                view.search_editing = false;
+            }
+            KeyCode::Enter => {
+                view.search_editing = false;
+                if !view.find(true) {
+                    app.status_message = Some("No match".to_string());
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(search) = &mut view.search {
+                    search.pop();
+                }
+            }
+            KeyCode::Char(c) => view.search.get_or_insert_with(String::new).push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    app.status_message = None;
     match key {
-        KeyCode::Esc | KeyCode::Char('s') => app.show_sound_settings = false,
-        KeyCode::Up => {
-            if app.sound_settings_row > 0 {
-                app.sound_settings_row -= 1;
+        KeyCode::Esc if view.search.is_some() => view.search = None,
+        KeyCode::Esc | KeyCode::Char('q') => app.transcript_view = None,
+        KeyCode::Up | KeyCode::Char('k') => view.select_prev(),
+        KeyCode::Down | KeyCode::Char('j') => view.select_next(),
+        KeyCode::PageUp => view.page(false),
+        KeyCode::PageDown | KeyCode::Char(' ') => view.page(true),
+        KeyCode::Home | KeyCode::Char('g') => view.select_first(),
+        KeyCode::End | KeyCode::Char('G') => {
+            view.select_last();
+            view.follow = true;
+        }
+        KeyCode::Char('f') => {
+            view.follow = !view.follow;
+            if view.follow {
+                view.select_last();
             }
         }
-        KeyCode::Down => {
-            if app.sound_settings_row < 7 {
-                app.sound_settings_row += 1;
+        KeyCode::Enter | KeyCode::Tab => view.toggle_expanded(),
+        KeyCode::Char('t') => view.toggle_thinking(),
+        KeyCode::Char('/') => {
+            view.search = Some(String::new());
+            view.search_editing = true;
+        }
+        KeyCode::Char(c @ ('n' | 'N')) => {
+            let found = view.find(c == 'n');
+            if !found {
+                app.status_message = Some("No match".to_string());
             }
         }
+        _ => {}
+    }
+}
+
+/// Opens the selected plate's transcript in the viewer.
+fn open_transcript(app: &mut App) {
+    let Some(plate) = app.selected_plate() else {
+        return;
+    };
+    // A remote plate's transcript is on another machine
+    let path = plate
+        .transcript_path
+        .clone()
+        .filter(|_| plate.host.is_none());
+    let Some(path) = path else {
+        app.status_message = Some("No transcript for this plate".to_string());
+        return;
+    };
+    let title = match &plate.git_branch {
+        Some(branch) => format!("{}/{}", plate.project_name(), branch),
+        None => plate.project_name().to_string(),
+    };
+    match TranscriptView::open(title, &path) {
+        Ok(view) => app.transcript_view = Some(view),
+        Err(e) => app.status_message = Some(format!("Can't read transcript: {}", e)),
+    }
+}
+
+async fn handle_inbox_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Tab | KeyCode::Esc => app.toggle_inbox_focus(),
+        KeyCode::Up => app.inbox_move_up(),
+        KeyCode::Down => app.inbox_move_down(),
+        KeyCode::Char('d') | KeyCode::Enter => app.start_dispatch(),
+        KeyCode::Char('x') => {
+            update_inbox_item(app, serde_json::json!({"status": "dismissed"})).await
+        }
+        KeyCode::Char('z') => {
+            let until = chrono::Utc::now() + chrono::Duration::seconds(SNOOZE_SECS);
+            update_inbox_item(
+                app,
+                serde_json::json!({"status": "snoozed", "snoozed_until": until.to_rfc3339()}),
+            )
+            .await
+        }
+        KeyCode::Char('r') => refresh(app).await,
+        _ => {}
+    }
+}
+
+async fn handle_settings_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc | KeyCode::Char('s') => app.show_sound_settings = false,
+        KeyCode::Up if app.sound_settings_row > 0 => app.sound_settings_row -= 1,
+        KeyCode::Down if app.sound_settings_row < 7 => app.sound_settings_row += 1,
         KeyCode::Left | KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => {
             let forward = matches!(key, KeyCode::Right | KeyCode::Enter | KeyCode::Char(' '));
             let sound_to_preview = match app.sound_settings_row {
@@ -180,8 +367,73 @@ async fn handle_settings_key(app: &mut App, key: KeyCode) {
     }
 }
 
+fn handle_filter_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc | KeyCode::Char('f') => app.show_filters = false,
+        KeyCode::Up if app.filter_row > 0 => app.filter_row -= 1,
+        KeyCode::Down if app.filter_row + 1 < FILTER_ROWS => app.filter_row += 1,
+        KeyCode::Left | KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => {
+            app.change_filter(key != KeyCode::Left);
+            let _ = save_config(&app.config);
+        }
+        KeyCode::Char('x') => {
+            app.clear_filter();
+            let _ = save_config(&app.config);
+        }
+        _ => {}
+    }
+}
+
+async fn handle_search_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => app.clear_search(),
+        KeyCode::Enter => {
+            app.search_editing = false;
+            if app.search_query.as_deref() == Some("") {
+                app.clear_search();
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(query) = app.search_query.as_mut() {
+                query.pop();
+            }
+            update_search(app).await;
+        }
+        KeyCode::Char(c) => {
+            app.search_query.get_or_insert_with(String::new).push(c);
+            update_search(app).await;
+        }
+        _ => {}
+    }
+}
+
+async fn update_search(app: &mut App) {
+    let query = app.search_query.clone().unwrap_or_default();
+    if query.trim().is_empty() {
+        let session = app.selected_session();
+        app.search_matches = None;
+        app.reselect(session.as_deref());
+        return;
+    }
+
+    let client = crate::api_token::client();
+    let url = format!("{}/search", DAEMON_URL);
+
+    let Ok(resp) = client.get(&url).query(&[("q", &query)]).send().await else {
+        return;
+    };
+
+    let Ok(matches): Result<Vec<Plate>, _> = resp.json().await else {
+        return;
+    };
+
+    let session = app.selected_session();
+    app.search_matches = Some(matches.into_iter().map(|p| p.session_id).collect());
+    app.reselect(session.as_deref());
+}
+
 async fn refresh(app: &mut App) {
-    let client = reqwest::Client::new();
+    let client = crate::api_token::client();
     let url = format!("{}/plates", DAEMON_URL);
 
     let Ok(resp) = client.get(&url).send().await else {
@@ -192,6 +444,193 @@ async fn refresh(app: &mut App) {
         return;
     };
 
+    apply_plates(app, plates).await;
+    refresh_inbox(app).await;
+    refresh_status(app).await;
+}
+
+/// Picks up summarizer failures from the daemon's `/status`.
+async fn refresh_status(app: &mut App) {
+    let client = crate::api_token::client();
+    let url = format!("{}/status", DAEMON_URL);
+
+    let Ok(resp) = client.get(&url).send().await else {
+        return;
+    };
+    let Ok(status): Result<serde_json::Value, _> = resp.json().await else {
+        return;
+    };
+    let health: SummarizerHealth =
+        serde_json::from_value(status["summarizer"].clone()).unwrap_or_default();
+    app.summarizer_failure = health.current_failure().cloned();
+}
+
+async fn refresh_inbox(app: &mut App) {
+    let client = crate::api_token::client();
+    let url = format!("{}/inbox", DAEMON_URL);
+
+    let Ok(resp) = client
+        .get(&url)
+        .query(&[("status", "pending")])
+        .send()
+        .await
+    else {
+        return;
+    };
+
+    let Ok(inbox): Result<Vec<InboxItem>, _> = resp.json().await else {
+        return;
+    };
+
+    app.set_inbox(inbox);
+}
+
+async fn update_inbox_item(app: &mut App, body: serde_json::Value) {
+    let Some(item) = app.selected_inbox_item() else {
+        return;
+    };
+
+    let client = crate::api_token::client();
+    let url = format!("{}/inbox/{}", DAEMON_URL, item.id);
+    let _ = client.patch(&url).json(&body).send().await;
+
+    refresh_inbox(app).await;
+}
+
+/// Sends the item being dispatched to the selected plate.
+async fn dispatch(app: &mut App) {
+    let (Some(id), Some(plate)) = (app.dispatching, app.selected_plate()) else {
+        return;
+    };
+    let session_id = plate.session_id.clone();
+    app.dispatching = None;
+
+    let client = crate::api_token::client();
+    let url = format!("{}/inbox/{}/dispatch", DAEMON_URL, id);
+    let result = client
+        .post(&url)
+        .json(&serde_json::json!({"session_id": session_id}))
+        .send()
+        .await;
+
+    app.status_message = Some(match result {
+        Ok(resp) if resp.status().is_success() => "Dispatched".to_string(),
+        Ok(resp) => {
+            let body: serde_json::Value = resp.json().await.unwrap_or_default();
+            let message = body["error"]["message"].as_str().unwrap_or("unknown error");
+            format!("Dispatch failed: {}", message)
+        }
+        Err(e) => format!("Dispatch failed: {}", e),
+    });
+
+    refresh_inbox(app).await;
+}
+
+/// Fetches what the selected plate did since it was last acknowledged,
+/// unless that was already fetched for its latest update. A failed fetch
+/// is tried again after `DIGEST_RETRY_SECS`.
+async fn load_digest(app: &mut App) {
+    let wanted = app.wanted_digest();
+    if wanted == app.digest_for {
+        return;
+    }
+    let Some(wanted) = wanted else {
+        app.digest_for = None;
+        app.digest = None;
+        return;
+    };
+    if app
+        .digest_failed
+        .as_ref()
+        .is_some_and(|(failed, at)| *failed == wanted && at.elapsed().as_secs() < DIGEST_RETRY_SECS)
+    {
+        return;
+    }
+    app.digest = None;
+
+    let client = crate::api_token::client();
+    let url = format!("{}/plates/{}/digest", DAEMON_URL, wanted.0);
+    let request = client
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(DIGEST_TIMEOUT_SECS));
+    let digest = match request.send().await.and_then(|r| r.error_for_status()) {
+        Ok(resp) => resp.json::<Digest>().await.ok(),
+        Err(_) => None,
+    };
+    match digest {
+        Some(digest) => {
+            app.digest = Some(digest).filter(|d| !d.entries.is_empty());
+            app.digest_for = Some(wanted);
+            app.digest_failed = None;
+        }
+        None => app.digest_failed = Some((wanted, std::time::Instant::now())),
+    }
+}
+
+/// Fetches the selected plate's plan and opens it in the overlay.
+async fn open_plan(app: &mut App) {
+    let Some(plate) = app.selected_plate().filter(|p| p.has_plan) else {
+        app.status_message = Some("No plan for this plate".to_string());
+        return;
+    };
+    let title = match &plate.git_branch {
+        Some(branch) => format!("{}/{}", plate.project_name(), branch),
+        None => plate.project_name().to_string(),
+    };
+
+    let client = crate::api_token::client();
+    let url = format!("{}/plates/{}", DAEMON_URL, plate.session_id);
+    let detail: Option<PlateDetail> = match client.get(&url).send().await {
+        Ok(resp) => resp.json().await.ok(),
+        Err(_) => None,
+    };
+    match detail.and_then(|d| d.plan) {
+        Some(plan) => app.plan_view = Some(PlanView::new(title, plan)),
+        None => app.status_message = Some("Couldn't load the plan".to_string()),
+    }
+}
+
+async fn load_detail(app: &mut App) {
+    let wanted = app.wanted_detail();
+    if wanted == app.detail_for {
+        return;
+    }
+    // Keep showing the old detail of the same plate until the new one loads
+    if wanted.as_ref().map(|w| &w.0) != app.detail_for.as_ref().map(|d| &d.0) {
+        app.detail = None;
+    }
+    app.detail_for = wanted.clone();
+    let Some((session_id, _)) = wanted else {
+        return;
+    };
+
+    let client = crate::api_token::client();
+    let url = format!("{}/plates/{}", DAEMON_URL, session_id);
+    let Ok(resp) = client.get(&url).send().await else {
+        return;
+    };
+    if let Ok(detail) = resp.json::<PlateDetail>().await {
+        app.detail = Some(detail);
+    }
+}
+
+/// Marks the selected plate as caught up on.
+async fn acknowledge(app: &mut App) {
+    if app.digest.is_none() {
+        return;
+    }
+    let Some((session_id, _)) = app.wanted_digest() else {
+        return;
+    };
+
+    let client = crate::api_token::client();
+    let url = format!("{}/plates/{}/ack", DAEMON_URL, session_id);
+    if client.post(&url).send().await.is_ok() {
+        app.digest = None;
+    }
+}
+
+async fn apply_plates(app: &mut App, plates: Vec<Plate>) {
     for plate in &plates {
         let prev_status = app.previous_statuses.get(&plate.session_id);
 
@@ -221,40 +660,74 @@ async fn refresh(app: &mut App) {
             .insert(plate.session_id.clone(), plate.status);
     }
 
+    // Keep the same plate selected as the list reorders
+    let session = app.selected_session();
     app.plates = plates;
 
-    if let Some(idx) = app.selected_index {
-        let max_idx = app.max_selectable_index();
-        let has_items = !app.open_plates().is_empty() || !app.closed_plates().is_empty();
-        if idx > max_idx {
-            app.selected_index = if has_items { Some(max_idx) } else { None };
-        }
+    if app.search_query.is_some() {
+        update_search(app).await;
     }
+    app.reselect(session.as_deref());
 }
 
 async fn dismiss(app: &mut App) {
     let Some(plate) = app.selected_plate() else {
         return;
     };
+    if plate.read_only {
+        app.status_message = Some(format!(
+            "Plates on {} are read-only",
+            plate.host.as_deref().unwrap_or("that host")
+        ));
+        return;
+    }
     let session_id = plate.session_id.clone();
 
-    let client = reqwest::Client::new();
+    let client = crate::api_token::client();
     let url = format!("{}/plates/{}", DAEMON_URL, session_id);
     let _ = client.delete(&url).send().await;
 
     refresh(app).await;
 }
 
-async fn connect_websocket(tx: mpsc::Sender<()>) {
+/// Follows the daemon's plate stream, forwarding messages that arrive in
+/// sequence and asking for a fresh snapshot whenever one goes missing.
+async fn connect_websocket(tx: mpsc::Sender<ServerMessage>) {
     let url = format!("{}/ws", DAEMON_URL.replace("http://", "ws://"));
+    let resync = serde_json::to_string(&ClientMessage::Resync).unwrap_or_default();
 
     loop {
-        if let Ok((ws_stream, _)) = tokio_tungstenite::connect_async(&url).await {
-            let (_, mut read) = ws_stream.split();
+        let Ok(mut request) = url.as_str().into_client_request() else {
+            return;
+        };
+        request
+            .headers_mut()
+            .extend(crate::api_token::auth_headers());
 
-            while let Some(msg) = read.next().await {
-                if msg.is_ok() {
-                    let _ = tx.send(()).await;
+        if let Ok((ws_stream, _)) = tokio_tungstenite::connect_async(request).await {
+            let (mut write, mut read) = ws_stream.split();
+            let mut tracker = SeqTracker::default();
+
+            while let Some(Ok(msg)) = read.next().await {
+                let Message::Text(text) = msg else {
+                    continue;
+                };
+                let Ok(msg) = serde_json::from_str::<ServerMessage>(&text) else {
+                    continue;
+                };
+
+                match tracker.receive(&msg) {
+                    Received::Apply => {}
+                    Received::Skip => continue,
+                    Received::Resync => {
+                        if write.send(Message::Text(resync.clone())).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+                if tx.send(msg).await.is_err() {
+                    return;
                 }
             }
         }
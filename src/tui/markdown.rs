@@ -0,0 +1,191 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Renders the markdown the TUI shows (plans, assistant text) into lines
+/// wrapped to `width`: headings, bullet and numbered lists, fenced code
+/// blocks, and inline `code` and **bold**. Anything else is plain text.
+pub fn render(text: &str, width: usize) -> Vec<Line<'static>> {
+    let width = width.max(10);
+    let mut lines = Vec::new();
+    let mut in_code = false;
+
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            let code = format!("  {}", raw.replace('\t', "    "));
+            for chunk in code.chars().collect::<Vec<_>>().chunks(width) {
+                lines.push(Line::from(Span::styled(
+                    chunk.iter().collect::<String>(),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            lines.push(Line::from(""));
+            continue;
+        }
+
+        if let Some((level, heading)) = heading(trimmed) {
+            let style = if level == 1 {
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            };
+            let spans = inline(heading, style);
+            lines.extend(wrap(spans, "", "", width));
+            continue;
+        }
+
+        let indent = " ".repeat(raw.len() - trimmed.len());
+        if let Some((marker, item)) = list_item(trimmed) {
+            let first = format!("{}{} ", indent, marker);
+            let rest = " ".repeat(first.chars().count());
+            lines.extend(wrap(inline(item, Style::default()), &first, &rest, width));
+        } else {
+            lines.extend(wrap(
+                inline(trimmed, Style::default()),
+                &indent,
+                &indent,
+                width,
+            ));
+        }
+    }
+    lines
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..]
+        .strip_prefix(' ')
+        .map(|text| (level, text.trim()))
+}
+
+/// The bullet to show and the item text, for `- `, `* `, `+ ` and `1. `
+/// items.
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), item));
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(item) = line[digits..].strip_prefix(". ") {
+            return Some((line[..digits + 1].to_string(), item));
+        }
+    }
+    None
+}
+
+/// Splits `**bold**` and `` `code` `` out of a line of text.
+fn inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let next = [
+            ("**", base.add_modifier(Modifier::BOLD)),
+            ("`", base.fg(Color::Cyan)),
+        ]
+        .into_iter()
+        .filter_map(|(delim, style)| {
+            let start = rest.find(delim)?;
+            let end = rest[start + delim.len()..].find(delim)?;
+            Some((start, end, delim, style))
+        })
+        .min_by_key(|(start, ..)| *start);
+        let Some((start, end, delim, style)) = next else {
+            spans.push(Span::styled(rest.to_string(), base));
+            break;
+        };
+        if start > 0 {
+            spans.push(Span::styled(rest[..start].to_string(), base));
+        }
+        let inner = &rest[start + delim.len()..start + delim.len() + end];
+        spans.push(Span::styled(inner.to_string(), style));
+        rest = &rest[start + 2 * delim.len() + end..];
+    }
+    spans
+}
+
+/// Word-wraps styled spans, starting the first line with `first` and the
+/// others with `rest`.
+fn wrap(spans: Vec<Span<'static>>, first: &str, rest: &str, width: usize) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut current: Vec<Span<'static>> = vec![Span::raw(first.to_string())];
+    let mut used = first.chars().count();
+    let mut at_start = true;
+
+    for span in spans {
+        for word in span.content.split_inclusive(' ') {
+            let len = word.trim_end().chars().count();
+            if !at_start && used + len > width {
+                lines.push(Line::from(std::mem::take(&mut current)));
+                current.push(Span::raw(rest.to_string()));
+                used = rest.chars().count();
+                at_start = true;
+            }
+            let word = if at_start { word.trim_start() } else { word };
+            if word.is_empty() {
+                continue;
+            }
+            used += word.chars().count();
+            at_start = false;
+            current.push(Span::styled(word.to_string(), span.style));
+        }
+    }
+    lines.push(Line::from(current));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn renders_headings_lists_and_code() {
+        let plan =
+            "# Plan\n\n1. Add the `plan` column\n- Render **bold** text\n```\nfn main() {}\n```";
+        let lines = render(plan, 80);
+        let texts: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Plan",
+                "",
+                "1. Add the plan column",
+                "• Render bold text",
+                "  fn main() {}",
+            ]
+        );
+        assert!(lines[0].spans[1]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+        assert_eq!(lines[2].spans[3].content, "plan");
+        assert_eq!(lines[2].spans[3].style.fg, Some(Color::Cyan));
+        assert_eq!(lines[4].spans[0].style.fg, Some(Color::Cyan));
+    }
+
+    #[test]
+    fn wraps_list_items_under_their_text() {
+        let lines = render("- one two three four five six", 14);
+        let texts: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(texts, vec!["• one two ", "  three four ", "  five six"]);
+    }
+}
@@ -1,5 +1,7 @@
 pub mod app;
+mod markdown;
 pub mod state;
+mod transcript;
 pub mod ui;
 
 pub use app::run;
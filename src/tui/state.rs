@@ -1,9 +1,12 @@
+use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
 use std::process::Command;
 
-use crate::config::{get_data_dir, Config};
+use super::transcript::TranscriptView;
+use crate::config::{get_data_dir, Config, SortMode, SummarizerBackend};
 use crate::daemon::summarizer::get_api_key;
-use crate::models::{Plate, PlateStatus};
+use crate::daemon::summarizer::health::FailureRecord;
+use crate::models::{Digest, InboxItem, Plate, PlateDetail, PlateStatus};
 
 pub struct App {
     pub plates: Vec<Plate>,
@@ -15,14 +18,118 @@ pub struct App {
     pub resume_plate: Option<(String, String)>,
     pub show_sound_settings: bool,
     pub sound_settings_row: usize,
+    /// Whether the filter menu is open, and the row it has selected.
+    pub show_filters: bool,
+    pub filter_row: usize,
     pub show_auth_banner: bool,
     pub closed_expanded: bool,
     pub status_message: Option<String>,
+    pub search_query: Option<String>,
+    pub search_editing: bool,
+    pub search_matches: Option<HashSet<String>>,
+    pub inbox: Vec<InboxItem>,
+    pub inbox_focused: bool,
+    pub inbox_index: usize,
+    /// Inbox item waiting for the user to pick the plate it goes to.
+    pub dispatching: Option<i64>,
+    /// What the selected plate did since it was last acknowledged.
+    pub digest: Option<Digest>,
+    /// Session id and `updated_at` of the plate the digest was fetched for.
+    pub digest_for: Option<(String, String)>,
+    /// The last digest fetch that failed, and when, so it is retried later
+    /// rather than on every frame.
+    pub digest_failed: Option<((String, String), std::time::Instant)>,
+    /// Why the model hasn't been writing summaries, if it hasn't.
+    pub summarizer_failure: Option<FailureRecord>,
+    /// The plan overlay, when open.
+    pub plan_view: Option<PlanView>,
+    /// Whether the detail pane is shown next to the plate list.
+    pub show_detail: bool,
+    /// Detail of the selected plate, for the detail pane.
+    pub detail: Option<PlateDetail>,
+    /// Session id and `updated_at` of the plate the detail was fetched for.
+    pub detail_for: Option<(String, String)>,
+    /// The full-screen transcript viewer, when open.
+    pub transcript_view: Option<TranscriptView>,
+}
+
+/// A plate's ExitPlanMode plan, shown in a scrollable overlay.
+pub struct PlanView {
+    pub title: String,
+    pub plan: String,
+    pub scroll: usize,
+    /// How far `scroll` can go at the current terminal size; updated on
+    /// each draw.
+    pub max_scroll: Cell<usize>,
+}
+
+impl PlanView {
+    pub fn new(title: String, plan: String) -> Self {
+        Self {
+            title,
+            plan,
+            scroll: 0,
+            max_scroll: Cell::new(0),
+        }
+    }
+
+    pub fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(lines)
+            .min(self.max_scroll.get());
+    }
+}
+
+/// Rows in the filter menu: one per status, then project, branch and
+/// "has todos".
+pub const FILTER_ROWS: usize = PlateStatus::ALL.len() + 3;
+
+fn sort_plates(plates: &mut [&Plate], mode: SortMode) {
+    // Timestamps are RFC 3339 in UTC, so they sort as strings
+    match mode {
+        SortMode::Attention => {
+            plates.sort_by_key(|p| !p.status.needs_attention());
+        }
+        SortMode::Updated => plates.sort_by(|a, b| b.updated_at.cmp(&a.updated_at)),
+        SortMode::Created => plates.sort_by(|a, b| b.created_at.cmp(&a.created_at)),
+        SortMode::Project => plates.sort_by(|a, b| {
+            a.project_name()
+                .cmp(b.project_name())
+                .then_with(|| a.git_branch.cmp(&b.git_branch))
+        }),
+        // A waiting plate last changed when it started waiting, so the
+        // oldest of those has waited longest
+        SortMode::Waiting => {
+            plates.sort_by(
+                |a, b| match (a.status.needs_attention(), b.status.needs_attention()) {
+                    (true, true) => a.updated_at.cmp(&b.updated_at),
+                    (false, false) => b.updated_at.cmp(&a.updated_at),
+                    (a_needs, b_needs) => b_needs.cmp(&a_needs),
+                },
+            )
+        }
+    }
+}
+
+/// The choice after (or before) `current` in `choices`, going through
+/// "any" (`None`) at either end.
+fn step_choice(current: Option<&str>, choices: &[String], forward: bool) -> Option<String> {
+    let pos = current.and_then(|c| choices.iter().position(|x| x == c));
+    let next = match (pos, forward) {
+        (None, true) => Some(0),
+        (None, false) => choices.len().checked_sub(1),
+        (Some(i), true) => Some(i + 1),
+        (Some(i), false) => i.checked_sub(1),
+    };
+    next.and_then(|i| choices.get(i)).cloned()
 }
 
 impl App {
     pub fn new(config: Config) -> Self {
-        let has_api_key = get_api_key().is_some();
+        // Only the Anthropic backend needs a key from `sp auth`
+        let has_api_key =
+            config.summarizer.backend != SummarizerBackend::Anthropic || get_api_key().is_some();
         let banner_dismissed = get_data_dir().join(".auth_banner_dismissed").exists();
         Self {
             plates: Vec::new(),
@@ -34,9 +141,27 @@ impl App {
             resume_plate: None,
             show_sound_settings: false,
             sound_settings_row: 0,
+            show_filters: false,
+            filter_row: 0,
             show_auth_banner: !has_api_key && !banner_dismissed,
             closed_expanded: false,
             status_message: None,
+            search_query: None,
+            search_editing: false,
+            search_matches: None,
+            inbox: Vec::new(),
+            inbox_focused: false,
+            inbox_index: 0,
+            dispatching: None,
+            digest: None,
+            digest_for: None,
+            digest_failed: None,
+            summarizer_failure: None,
+            plan_view: None,
+            show_detail: false,
+            detail: None,
+            detail_for: None,
+            transcript_view: None,
         }
     }
 
@@ -46,27 +171,99 @@ impl App {
         let _ = std::fs::write(&dismiss_path, "");
     }
 
+    pub fn start_search(&mut self) {
+        self.search_query.get_or_insert_with(String::new);
+        self.search_editing = true;
+    }
+
+    pub fn clear_search(&mut self) {
+        let session = self.selected_session();
+        self.search_query = None;
+        self.search_editing = false;
+        self.search_matches = None;
+        self.reselect(session.as_deref());
+    }
+
+    fn matches_search(&self, plate: &Plate) -> bool {
+        self.search_matches
+            .as_ref()
+            .is_none_or(|matches| matches.contains(&plate.session_id))
+    }
+
+    fn is_listed(&self, plate: &Plate) -> bool {
+        self.matches_search(plate) && self.config.dashboard.filter.matches(plate)
+    }
+
+    /// Session id of the selected plate, to find it again with `reselect`
+    /// after the list changes order.
+    pub fn selected_session(&self) -> Option<String> {
+        self.selected_plate().map(|p| p.session_id.clone())
+    }
+
+    /// Moves the selection to wherever `session_id` is listed now, or
+    /// keeps the index in range if it's gone.
+    pub fn reselect(&mut self, session_id: Option<&str>) {
+        if let Some(idx) = session_id.and_then(|id| self.display_index(id)) {
+            self.selected_index = Some(idx);
+        } else {
+            self.clamp_selection();
+        }
+    }
+
+    fn display_index(&self, session_id: &str) -> Option<usize> {
+        let open = self.open_plates();
+        if let Some(idx) = open.iter().position(|p| p.session_id == session_id) {
+            return Some(idx);
+        }
+        if !self.closed_expanded {
+            return None;
+        }
+        let closed_idx = self
+            .closed_plates()
+            .iter()
+            .position(|p| p.session_id == session_id)?;
+        // Explicit mode puts a header row before the closed plates
+        let header = usize::from(!self.config.minimal_mode);
+        Some(open.len() + header + closed_idx)
+    }
+
+    pub fn cycle_sort(&mut self) {
+        let session = self.selected_session();
+        self.config.dashboard.sort = self.config.dashboard.sort.next();
+        self.reselect(session.as_deref());
+    }
+
+    pub fn clamp_selection(&mut self) {
+        if let Some(idx) = self.selected_index {
+            let max_idx = self.max_selectable_index();
+            let has_items = !self.open_plates().is_empty() || !self.closed_plates().is_empty();
+            if idx > max_idx {
+                self.selected_index = if has_items { Some(max_idx) } else { None };
+            }
+        }
+    }
+
     pub fn open_plates(&self) -> Vec<&Plate> {
         let mut open: Vec<_> = self
             .plates
             .iter()
-            .filter(|s| s.status != PlateStatus::Closed)
+            .filter(|s| s.status != PlateStatus::Closed && self.is_listed(s))
             .collect();
-
-        open.sort_by(|a, b| {
-            let a_needs = a.status.needs_attention();
-            let b_needs = b.status.needs_attention();
-            b_needs.cmp(&a_needs)
-        });
-
+        sort_plates(&mut open, self.config.dashboard.sort);
         open
     }
 
     pub fn closed_plates(&self) -> Vec<&Plate> {
-        self.plates
+        let mut closed: Vec<_> = self
+            .plates
             .iter()
-            .filter(|s| s.status == PlateStatus::Closed)
-            .collect()
+            .filter(|s| s.status == PlateStatus::Closed && self.is_listed(s))
+            .collect();
+        // Closed plates keep the daemon's order unless a sort says otherwise
+        if self.config.dashboard.sort != SortMode::Attention {
+            sort_plates(&mut closed, self.config.dashboard.sort);
+        }
+        closed
     }
 
     pub fn display_order(&self) -> Vec<&Plate> {
@@ -78,6 +275,58 @@ impl App {
         open
     }
 
+    /// Projects and branches of the current plates, for the filter menu.
+    pub fn filter_choices(&self) -> (Vec<String>, Vec<String>) {
+        let mut projects: Vec<String> = self
+            .plates
+            .iter()
+            .map(|p| p.project_name().to_string())
+            .collect();
+        let mut branches: Vec<String> = self
+            .plates
+            .iter()
+            .filter_map(|p| p.git_branch.clone())
+            .collect();
+        for list in [&mut projects, &mut branches] {
+            list.sort();
+            list.dedup();
+        }
+        (projects, branches)
+    }
+
+    /// Changes the filter on the selected menu row: toggles a status or
+    /// "has todos", or steps the project or branch through what's listed.
+    pub fn change_filter(&mut self, forward: bool) {
+        let session = self.selected_session();
+        let (projects, branches) = self.filter_choices();
+        let filter = &mut self.config.dashboard.filter;
+        let statuses = PlateStatus::ALL.len();
+        match self.filter_row {
+            row if row < statuses => {
+                let status = PlateStatus::ALL[row];
+                if let Some(pos) = filter.statuses.iter().position(|s| *s == status) {
+                    filter.statuses.remove(pos);
+                } else {
+                    filter.statuses.push(status);
+                }
+            }
+            row if row == statuses => {
+                filter.project = step_choice(filter.project.as_deref(), &projects, forward)
+            }
+            row if row == statuses + 1 => {
+                filter.branch = step_choice(filter.branch.as_deref(), &branches, forward)
+            }
+            _ => filter.has_todos = !filter.has_todos,
+        }
+        self.reselect(session.as_deref());
+    }
+
+    pub fn clear_filter(&mut self) {
+        let session = self.selected_session();
+        self.config.dashboard.filter = Default::default();
+        self.reselect(session.as_deref());
+    }
+
     pub fn is_on_closed_header(&self) -> bool {
         if self.config.minimal_mode {
             return false;
@@ -150,6 +399,11 @@ impl App {
             return;
         };
 
+        if let Some(host) = &plate.host {
+            self.status_message = Some(format!("This plate runs on {}", host));
+            return;
+        }
+
         let is_closed = plate.status == PlateStatus::Closed;
 
         if is_closed {
@@ -220,6 +474,24 @@ impl App {
         }
     }
 
+    /// Which digest the selection calls for: the selected local plate as of
+    /// its latest update.
+    pub fn wanted_digest(&self) -> Option<(String, String)> {
+        self.selected_plate()
+            .filter(|p| p.host.is_none())
+            .map(|p| (p.session_id.clone(), p.updated_at.clone()))
+    }
+
+    /// The plate the detail pane should show, keyed like `wanted_digest`
+    /// so it's fetched again whenever the plate changes.
+    pub fn wanted_detail(&self) -> Option<(String, String)> {
+        if !self.show_detail {
+            return None;
+        }
+        self.selected_plate()
+            .map(|p| (p.session_id.clone(), p.updated_at.clone()))
+    }
+
     pub fn is_unseen(&self, session_id: &str) -> bool {
         !self.seen_plates.contains(session_id)
     }
@@ -230,4 +502,138 @@ impl App {
             .filter(|s| s.status.needs_attention() && self.is_unseen(&s.session_id))
             .count()
     }
+
+    pub fn set_inbox(&mut self, inbox: Vec<InboxItem>) {
+        self.inbox = inbox;
+        self.inbox_index = self.inbox_index.min(self.inbox.len().saturating_sub(1));
+        if self.inbox.is_empty() {
+            self.inbox_focused = false;
+        }
+        if let Some(id) = self.dispatching {
+            if !self.inbox.iter().any(|item| item.id == id) {
+                self.dispatching = None;
+            }
+        }
+    }
+
+    pub fn toggle_inbox_focus(&mut self) {
+        self.inbox_focused = !self.inbox_focused && !self.inbox.is_empty();
+    }
+
+    pub fn inbox_move_up(&mut self) {
+        self.inbox_index = self.inbox_index.saturating_sub(1);
+    }
+
+    pub fn inbox_move_down(&mut self) {
+        if self.inbox_index + 1 < self.inbox.len() {
+            self.inbox_index += 1;
+        }
+    }
+
+    pub fn selected_inbox_item(&self) -> Option<&InboxItem> {
+        self.inbox.get(self.inbox_index)
+    }
+
+    pub fn dispatching_item(&self) -> Option<&InboxItem> {
+        let id = self.dispatching?;
+        self.inbox.iter().find(|item| item.id == id)
+    }
+
+    /// Starts choosing a plate for the selected inbox item.
+    pub fn start_dispatch(&mut self) {
+        if let Some(item) = self.selected_inbox_item() {
+            self.dispatching = Some(item.id);
+            self.inbox_focused = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PlateFilter;
+
+    /// A plate on `main`, created and last updated `created` and `updated`
+    /// seconds past midnight.
+    fn plate(
+        session_id: &str,
+        project: &str,
+        status: PlateStatus,
+        created: u32,
+        updated: u32,
+    ) -> Plate {
+        let at = |secs: u32| format!("2026-01-01T00:00:{:02}+00:00", secs);
+        Plate {
+            git_branch: Some("main".to_string()),
+            created_at: at(created),
+            updated_at: at(updated),
+            ..Plate::for_test(session_id, &format!("/work/{}", project), status)
+        }
+    }
+
+    fn ids(plates: &[&Plate]) -> Vec<String> {
+        plates.iter().map(|p| p.session_id.clone()).collect()
+    }
+
+    #[test]
+    fn sorts_by_each_mode() {
+        let plates = [
+            plate("a", "web", PlateStatus::Running, 1, 5),
+            plate("b", "api", PlateStatus::AwaitingInput, 2, 3),
+            plate("c", "cli", PlateStatus::Idle, 3, 1),
+            plate("d", "api", PlateStatus::Running, 4, 7),
+        ];
+        let sorted = |mode| {
+            let mut list: Vec<&Plate> = plates.iter().collect();
+            sort_plates(&mut list, mode);
+            ids(&list)
+        };
+        assert_eq!(sorted(SortMode::Attention), ["b", "c", "a", "d"]);
+        assert_eq!(sorted(SortMode::Updated), ["d", "a", "b", "c"]);
+        assert_eq!(sorted(SortMode::Created), ["d", "c", "b", "a"]);
+        assert_eq!(sorted(SortMode::Project), ["b", "d", "c", "a"]);
+        assert_eq!(sorted(SortMode::Waiting), ["c", "b", "d", "a"]);
+    }
+
+    #[test]
+    fn filters_by_status_project_branch_and_todos() {
+        let mut with_todos = plate("a", "api", PlateStatus::Idle, 0, 0);
+        with_todos.todo_progress = Some("1/3".to_string());
+        let mut empty_todos = plate("b", "api", PlateStatus::Running, 0, 0);
+        empty_todos.todo_progress = Some("0/0".to_string());
+
+        let mut filter = PlateFilter::default();
+        assert!(filter.matches(&with_todos) && filter.matches(&empty_todos));
+
+        filter.has_todos = true;
+        assert!(filter.matches(&with_todos) && !filter.matches(&empty_todos));
+
+        filter.statuses = vec![PlateStatus::Running];
+        assert!(!filter.matches(&with_todos));
+        filter.statuses.push(PlateStatus::Idle);
+        assert!(filter.matches(&with_todos));
+
+        filter.project = Some("/work/api".to_string());
+        assert!(filter.matches(&with_todos));
+        filter.project = Some("web".to_string());
+        assert!(!filter.matches(&with_todos));
+
+        filter.project = None;
+        filter.branch = Some("dev".to_string());
+        assert!(!filter.matches(&with_todos));
+    }
+
+    #[test]
+    fn choices_step_through_any_at_both_ends() {
+        let choices = vec!["api".to_string(), "web".to_string()];
+        assert_eq!(step_choice(None, &choices, true).as_deref(), Some("api"));
+        assert_eq!(
+            step_choice(Some("api"), &choices, true).as_deref(),
+            Some("web")
+        );
+        assert_eq!(step_choice(Some("web"), &choices, true), None);
+        assert_eq!(step_choice(None, &choices, false).as_deref(), Some("web"));
+        assert_eq!(step_choice(Some("api"), &choices, false), None);
+        assert_eq!(step_choice(Some("gone"), &[], true), None);
+    }
 }
@@ -0,0 +1,544 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use super::markdown;
+use crate::daemon::transcript::{tool_target, Tail};
+
+/// Output lines shown for an expanded tool call.
+const OUTPUT_LINES: usize = 200;
+/// How often an open transcript is checked for new lines.
+pub const POLL_MILLIS: u128 = 500;
+
+/// One thing shown in the transcript viewer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    User(String),
+    Assistant(String),
+    Thinking(String),
+    Tool {
+        name: String,
+        /// Pretty-printed input.
+        input: String,
+        target: Option<String>,
+        /// The result, once it arrives.
+        output: Option<String>,
+        is_error: bool,
+    },
+}
+
+impl Item {
+    fn text(&self) -> String {
+        match self {
+            Item::User(text) | Item::Assistant(text) | Item::Thinking(text) => text.clone(),
+            Item::Tool {
+                name,
+                input,
+                output,
+                ..
+            } => format!("{}\n{}\n{}", name, input, output.as_deref().unwrap_or("")),
+        }
+    }
+}
+
+/// The full-screen transcript viewer: a cursor over items, which are
+/// rendered to lines when they change and reused on later draws.
+pub struct TranscriptView {
+    pub title: String,
+    path: String,
+    tail: Tail,
+    pub items: Vec<Item>,
+    /// Tool calls waiting for their result, by `tool_use` id.
+    pending_tools: HashMap<String, usize>,
+    pub selected: usize,
+    pub expanded: HashSet<usize>,
+    pub show_thinking: bool,
+    /// Keep the newest item selected and in view as lines arrive.
+    pub follow: bool,
+    pub search: Option<String>,
+    pub search_editing: bool,
+    pub last_polled: std::time::Instant,
+    /// First line shown; kept so the selection stays in view on each draw.
+    pub scroll: Cell<usize>,
+    /// Lines that fit on screen, as of the last draw.
+    pub page: Cell<usize>,
+    /// Start line and line count of each item, as of the last draw.
+    pub layout: RefCell<Vec<(usize, usize)>>,
+    /// Lines of each item as last rendered, unselected; `None` when stale.
+    rendered: RefCell<Vec<Option<Vec<Line<'static>>>>>,
+    /// Width `rendered` was wrapped to.
+    rendered_width: Cell<usize>,
+}
+
+impl TranscriptView {
+    pub fn open(title: String, path: &str) -> std::io::Result<Self> {
+        let mut view = Self {
+            title,
+            path: path.to_string(),
+            tail: Tail::default(),
+            items: Vec::new(),
+            pending_tools: HashMap::new(),
+            selected: 0,
+            expanded: HashSet::new(),
+            show_thinking: false,
+            follow: true,
+            search: None,
+            search_editing: false,
+            last_polled: std::time::Instant::now(),
+            scroll: Cell::new(0),
+            page: Cell::new(0),
+            layout: RefCell::new(Vec::new()),
+            rendered: RefCell::new(Vec::new()),
+            rendered_width: Cell::new(0),
+        };
+        view.poll()?;
+        Ok(view)
+    }
+
+    /// Reads what was appended to the transcript since the last poll.
+    pub fn poll(&mut self) -> std::io::Result<()> {
+        self.last_polled = std::time::Instant::now();
+        let appended = self.tail.read(&self.path)?;
+        if appended.restarted {
+            self.items.clear();
+            self.pending_tools.clear();
+            self.expanded.clear();
+            self.rendered.borrow_mut().clear();
+            self.selected = 0;
+        }
+        for entry in appended.entries.into_iter().flatten() {
+            self.push_entry(&entry);
+        }
+        if self.follow {
+            self.select_last();
+        }
+        Ok(())
+    }
+
+    fn push_entry(&mut self, entry: &serde_json::Value) {
+        if entry.get("isMeta").and_then(|v| v.as_bool()) == Some(true) {
+            return;
+        }
+        let entry_type = entry.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        let Some(content) = entry.get("message").and_then(|m| m.get("content")) else {
+            return;
+        };
+        let blocks = match content {
+            serde_json::Value::String(text) => {
+                let text = text.trim().to_string();
+                if !text.is_empty() {
+                    self.items.push(match entry_type {
+                        "user" => Item::User(text),
+                        _ => Item::Assistant(text),
+                    });
+                }
+                return;
+            }
+            serde_json::Value::Array(blocks) => blocks,
+            _ => return,
+        };
+
+        for block in blocks {
+            let text = |key: &str| {
+                block
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string)
+            };
+            match block.get("type").and_then(|v| v.as_str()) {
+                Some("text") => {
+                    if let Some(text) = text("text") {
+                        self.items.push(match entry_type {
+                            "user" => Item::User(text),
+                            _ => Item::Assistant(text),
+                        });
+                    }
+                }
+                Some("thinking") => {
+                    if let Some(text) = text("thinking") {
+                        self.items.push(Item::Thinking(text));
+                    }
+                }
+                Some("tool_use") => {
+                    let input = block.get("input").cloned().unwrap_or_default();
+                    if let Some(id) = text("id") {
+                        self.pending_tools.insert(id, self.items.len());
+                    }
+                    self.items.push(Item::Tool {
+                        name: text("name").unwrap_or_else(|| "unknown".to_string()),
+                        input: serde_json::to_string_pretty(&input).unwrap_or_default(),
+                        target: tool_target(&input),
+                        output: None,
+                        is_error: false,
+                    });
+                }
+                Some("tool_result") => {
+                    let Some(idx) =
+                        text("tool_use_id").and_then(|id| self.pending_tools.remove(&id))
+                    else {
+                        continue;
+                    };
+                    if let Some(Item::Tool {
+                        output, is_error, ..
+                    }) = self.items.get_mut(idx)
+                    {
+                        *output = Some(result_text(block.get("content")));
+                        *is_error = block.get("is_error").and_then(|v| v.as_bool()) == Some(true);
+                        self.invalidate(idx);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Drops an item's rendered lines so the next draw renders it again.
+    fn invalidate(&self, idx: usize) {
+        if let Some(lines) = self.rendered.borrow_mut().get_mut(idx) {
+            *lines = None;
+        }
+    }
+
+    fn is_shown(&self, idx: usize) -> bool {
+        self.show_thinking || !matches!(self.items.get(idx), Some(Item::Thinking(_)))
+    }
+
+    pub fn select_next(&mut self) {
+        if let Some(next) = (self.selected + 1..self.items.len()).find(|&i| self.is_shown(i)) {
+            self.selected = next;
+        }
+        self.follow = false;
+    }
+
+    pub fn select_prev(&mut self) {
+        if let Some(prev) = (0..self.selected).rev().find(|&i| self.is_shown(i)) {
+            self.selected = prev;
+        }
+        self.follow = false;
+    }
+
+    pub fn select_first(&mut self) {
+        self.selected = 0;
+        self.follow = false;
+        if !self.is_shown(0) {
+            self.select_next();
+        }
+    }
+
+    pub fn select_last(&mut self) {
+        if let Some(last) = (0..self.items.len()).rev().find(|&i| self.is_shown(i)) {
+            self.selected = last;
+        }
+    }
+
+    /// Moves a screen down or up, selecting the first item that starts on
+    /// the new screen.
+    pub fn page(&mut self, down: bool) {
+        let page = self.page.get().max(1);
+        let scroll = self.scroll.get();
+        let target = if down {
+            scroll + page
+        } else {
+            scroll.saturating_sub(page)
+        };
+        let layout = self.layout.borrow();
+        let found = layout
+            .iter()
+            .enumerate()
+            .find(|(i, (start, len))| *len > 0 && *start >= target && self.is_shown(*i))
+            .map(|(i, _)| i);
+        drop(layout);
+        match found {
+            Some(idx) => {
+                self.selected = idx;
+                self.scroll.set(target);
+            }
+            None if down => self.select_last(),
+            None => self.select_first(),
+        }
+        self.follow = false;
+    }
+
+    pub fn toggle_expanded(&mut self) {
+        if matches!(self.items.get(self.selected), Some(Item::Tool { .. }))
+            && !self.expanded.remove(&self.selected)
+        {
+            self.expanded.insert(self.selected);
+        }
+        self.invalidate(self.selected);
+    }
+
+    pub fn toggle_thinking(&mut self) {
+        self.show_thinking = !self.show_thinking;
+        if !self.is_shown(self.selected) {
+            self.select_prev();
+        }
+    }
+
+    /// Selects the next item (or previous, going back) containing the
+    /// search text, wrapping around. Returns false when nothing matches.
+    pub fn find(&mut self, forward: bool) -> bool {
+        let Some(query) = self.search.as_deref().map(str::to_lowercase) else {
+            return false;
+        };
+        if query.is_empty() || self.items.is_empty() {
+            return false;
+        }
+        let n = self.items.len();
+        let found = (1..=n)
+            .map(|step| {
+                if forward {
+                    (self.selected + step) % n
+                } else {
+                    (self.selected + n - step % n) % n
+                }
+            })
+            .find(|&i| self.is_shown(i) && self.items[i].text().to_lowercase().contains(&query));
+        match found {
+            Some(idx) => {
+                self.selected = idx;
+                self.follow = false;
+                // Show what matched inside a tool call
+                if matches!(self.items[idx], Item::Tool { .. }) && self.expanded.insert(idx) {
+                    self.invalidate(idx);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Lines of every item wrapped to `width`, recording where each one
+    /// starts. Only items that changed since the last call are rendered.
+    pub fn lines(&self, width: usize) -> Vec<Line<'static>> {
+        let mut rendered = self.rendered.borrow_mut();
+        if self.rendered_width.replace(width) != width {
+            rendered.clear();
+        }
+        rendered.resize(self.items.len(), None);
+
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        let mut layout = Vec::with_capacity(self.items.len());
+        for (idx, item) in self.items.iter().enumerate() {
+            let start = lines.len();
+            if self.is_shown(idx) {
+                let item_lines =
+                    rendered[idx].get_or_insert_with(|| self.render_item(idx, item, width));
+                lines.extend(item_lines.iter().cloned());
+                if idx == self.selected {
+                    for span in lines[start].spans.iter_mut() {
+                        span.style = span.style.add_modifier(Modifier::REVERSED);
+                    }
+                }
+                lines.push(Line::from(""));
+            }
+            layout.push((start, lines.len() - start));
+        }
+        *self.layout.borrow_mut() = layout;
+        lines
+    }
+
+    fn render_item(&self, idx: usize, item: &Item, width: usize) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        let header = |text: String, style: Style| Line::from(Span::styled(text, style));
+        let dim = Style::default().add_modifier(Modifier::DIM);
+
+        match item {
+            Item::User(text) => {
+                lines.push(header(
+                    "You".to_string(),
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ));
+                lines.extend(markdown::render(text, width));
+            }
+            Item::Assistant(text) => {
+                lines.push(header(
+                    "Claude".to_string(),
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                ));
+                lines.extend(markdown::render(text, width));
+            }
+            Item::Thinking(text) => {
+                lines.push(header("Thinking".to_string(), dim));
+                for line in markdown::render(text, width) {
+                    lines.push(line.patch_style(dim));
+                }
+            }
+            Item::Tool {
+                name,
+                input,
+                target,
+                output,
+                is_error,
+            } => {
+                let expanded = self.expanded.contains(&idx);
+                let (state, style) = match (output, is_error) {
+                    (None, _) => ("...", Style::default().fg(Color::Yellow)),
+                    (Some(_), true) => ("error", Style::default().fg(Color::Red)),
+                    (Some(_), false) => ("ok", Style::default().fg(Color::Blue)),
+                };
+                let target = target
+                    .as_deref()
+                    .and_then(|t| t.lines().next())
+                    .map(|t| format!(" {}", t))
+                    .unwrap_or_default();
+                let marker = if expanded { "v" } else { ">" };
+                let text: String = format!("{} {}{} ({})", marker, name, target, state)
+                    .chars()
+                    .take(width)
+                    .collect();
+                lines.push(header(text, style));
+                if !expanded {
+                    return lines;
+                }
+                lines.push(Line::from(Span::styled("  input:", dim)));
+                push_wrapped(&mut lines, input, width, Style::default().fg(Color::Cyan));
+                if let Some(output) = output {
+                    lines.push(Line::from(Span::styled("  output:", dim)));
+                    let total = output.lines().count();
+                    let shown: String = output
+                        .lines()
+                        .take(OUTPUT_LINES)
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    push_wrapped(&mut lines, &shown, width, Style::default());
+                    if total > OUTPUT_LINES {
+                        lines.push(Line::from(Span::styled(
+                            format!("    ... {} more lines", total - OUTPUT_LINES),
+                            dim,
+                        )));
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// Adds `text` indented by four spaces, hard-wrapped to `width`.
+fn push_wrapped(lines: &mut Vec<Line<'static>>, text: &str, width: usize, style: Style) {
+    let room = width.saturating_sub(4).max(10);
+    for line in text.lines() {
+        let chars: Vec<char> = line.replace('\t', "    ").chars().collect();
+        if chars.is_empty() {
+            lines.push(Line::from(""));
+        }
+        for chunk in chars.chunks(room) {
+            lines.push(Line::from(Span::styled(
+                format!("    {}", chunk.iter().collect::<String>()),
+                style,
+            )));
+        }
+    }
+}
+
+/// The text of a `tool_result`, given as a string or as content blocks.
+fn result_text(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(serde_json::Value::Array(blocks)) => blocks
+            .iter()
+            .map(|block| match block.get("type").and_then(|v| v.as_str()) {
+                Some("text") => block
+                    .get("text")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                Some(other) => format!("[{}]", other),
+                None => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempFile;
+
+    const PROMPT: &str = r#"{"type":"user","message":{"content":"fix the login redirect"}}"#;
+    const THINK_AND_READ: &str = r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"The callback drops state"},{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"/work/api/src/auth.rs"}}]}}"#;
+    const RESULT: &str = r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"fn callback() {}"}]}}"#;
+    const REPLY: &str = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Found the bug in **auth.rs**."}]}}"#;
+
+    #[test]
+    fn pairs_tool_results_and_follows_new_lines() {
+        let file = TempFile::new("follow");
+        file.append_lines(&[PROMPT, THINK_AND_READ]);
+        let mut view = TranscriptView::open("api".to_string(), file.path()).unwrap();
+        assert_eq!(view.items.len(), 3);
+        assert!(matches!(&view.items[2], Item::Tool { output: None, .. }));
+        assert_eq!(view.selected, 2);
+        let header = |view: &TranscriptView| view.lines(80)[view.layout.borrow()[2].0].to_string();
+        assert_eq!(header(&view), "> Read /work/api/src/auth.rs (...)");
+
+        file.append_lines(&[RESULT, REPLY]);
+        view.poll().unwrap();
+        assert_eq!(view.items.len(), 4);
+        match &view.items[2] {
+            Item::Tool { target, output, .. } => {
+                assert_eq!(target.as_deref(), Some("/work/api/src/auth.rs"));
+                assert_eq!(output.as_deref(), Some("fn callback() {}"));
+            }
+            other => panic!("expected a tool call, got {:?}", other),
+        }
+        assert_eq!(view.selected, 3);
+        // The rendered call picks up its result
+        assert_eq!(header(&view), "> Read /work/api/src/auth.rs (ok)");
+    }
+
+    #[test]
+    fn hides_thinking_and_collapses_tools() {
+        let file = TempFile::new("render");
+        file.append_lines(&[PROMPT, THINK_AND_READ, RESULT, REPLY]);
+        let mut view = TranscriptView::open("api".to_string(), file.path()).unwrap();
+        let text = |view: &TranscriptView| -> Vec<String> {
+            view.lines(80)
+                .iter()
+                .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+                .collect()
+        };
+
+        let collapsed = text(&view);
+        assert!(!collapsed.iter().any(|l| l.contains("callback drops")));
+        assert!(collapsed.contains(&"> Read /work/api/src/auth.rs (ok)".to_string()));
+        assert!(!collapsed.iter().any(|l| l.contains("fn callback")));
+
+        view.selected = 2;
+        view.toggle_expanded();
+        view.toggle_thinking();
+        let expanded = text(&view);
+        assert!(expanded.iter().any(|l| l.contains("callback drops")));
+        assert!(expanded.contains(&"    fn callback() {}".to_string()));
+        assert_eq!(view.layout.borrow()[1].1, 3);
+    }
+
+    #[test]
+    fn search_wraps_around() {
+        let file = TempFile::new("search");
+        file.append_lines(&[PROMPT, THINK_AND_READ, RESULT, REPLY]);
+        let mut view = TranscriptView::open("api".to_string(), file.path()).unwrap();
+        view.search = Some("LOGIN".to_string());
+        assert!(view.find(true));
+        assert_eq!(view.selected, 0);
+        view.search = Some("callback()".to_string());
+        assert!(view.find(false));
+        assert_eq!(view.selected, 2);
+        assert!(view.expanded.contains(&2));
+        // Thinking is hidden, so its text isn't found
+        view.search = Some("drops state".to_string());
+        assert!(!view.find(true));
+    }
+}
@@ -2,21 +2,46 @@ use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
-use crate::config::{AVAILABLE_SOUNDS, AVAILABLE_THEMES};
-use crate::models::{Plate, PlateStatus};
+use crate::config::{PlateFilter, SortMode, AVAILABLE_SOUNDS, AVAILABLE_THEMES};
+use crate::daemon::transcript::tool_target;
+use crate::models::{local_time, Digest, Plate, PlateStatus};
 
-use super::state::App;
+use super::markdown;
+use super::state::{App, PlanView, FILTER_ROWS};
+use super::transcript::TranscriptView;
+
+const MAX_INBOX_ROWS: u16 = 6;
+const MAX_DIGEST_ROWS: u16 = 5;
+/// Terminal width from which the detail pane goes beside the list.
+const DETAIL_SIDE_MIN_WIDTH: u16 = 120;
+/// Tool calls listed in the detail pane.
+const DETAIL_TOOLS: usize = 8;
 
 pub fn render(frame: &mut Frame, app: &App) {
+    if let Some(view) = &app.transcript_view {
+        render_transcript(frame, app, view);
+        return;
+    }
     let banner_height = if app.show_auth_banner { 1 } else { 0 };
+    let inbox_height = if app.inbox.is_empty() {
+        0
+    } else {
+        (app.inbox.len() as u16).min(MAX_INBOX_ROWS) + 2
+    };
+    let digest_height = match &app.digest {
+        Some(digest) => (digest.entries.len() as u16).min(MAX_DIGEST_ROWS) + 2,
+        None => 0,
+    };
     let chunks = Layout::vertical([
         Constraint::Length(2),
         Constraint::Length(banner_height),
         Constraint::Min(0),
+        Constraint::Length(digest_height),
+        Constraint::Length(inbox_height),
         Constraint::Length(1),
     ])
     .split(frame.area());
@@ -25,12 +50,37 @@ pub fn render(frame: &mut Frame, app: &App) {
     if app.show_auth_banner {
         render_auth_banner(frame, chunks[1]);
     }
-    render_plates(frame, app, chunks[2]);
-    render_footer(frame, app, chunks[3]);
+    if app.show_detail {
+        // Beside the list on wide terminals, below it otherwise
+        let panes = if chunks[2].width >= DETAIL_SIDE_MIN_WIDTH {
+            Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(chunks[2])
+        } else {
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(chunks[2])
+        };
+        render_plates(frame, app, panes[0]);
+        render_detail(frame, app, panes[1]);
+    } else {
+        render_plates(frame, app, chunks[2]);
+    }
+    if let Some(digest) = &app.digest {
+        render_digest(frame, digest, chunks[3]);
+    }
+    if !app.inbox.is_empty() {
+        render_inbox(frame, app, chunks[4]);
+    }
+    render_footer(frame, app, chunks[5]);
 
     if app.show_sound_settings {
         render_sound_settings(frame, app);
     }
+    if app.show_filters {
+        render_filters(frame, app);
+    }
+    if let Some(view) = &app.plan_view {
+        render_plan(frame, view);
+    }
 }
 
 fn render_header(frame: &mut Frame, app: &App, area: Rect) {
@@ -41,8 +91,56 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         " Plate Spinner ".to_string()
     };
 
-    let header = Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD));
-    frame.render_widget(header, area);
+    let mut spans = vec![Span::styled(
+        title,
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if let Some(query) = &app.search_query {
+        let cursor = if app.search_editing { "_" } else { "" };
+        spans.push(Span::raw(format!(" /{}{}", query, cursor)));
+    }
+    let sort = app.config.dashboard.sort;
+    if sort != SortMode::Attention {
+        spans.push(Span::styled(
+            format!(" sort:{}", sort.as_str()),
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+    for chip in filter_chips(&app.config.dashboard.filter) {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(chip, Style::default().fg(Color::Cyan)));
+    }
+    if let Some(failure) = &app.summarizer_failure {
+        spans.push(Span::styled(
+            format!(" summaries: {} ", failure.message),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// The active filters, as shown in the header.
+fn filter_chips(filter: &PlateFilter) -> Vec<String> {
+    let mut chips = Vec::new();
+    if !filter.statuses.is_empty() {
+        let names: Vec<&str> = PlateStatus::ALL
+            .iter()
+            .filter(|s| filter.statuses.contains(s))
+            .map(|s| s.short_name())
+            .collect();
+        chips.push(format!("[{}]", names.join(",")));
+    }
+    if let Some(project) = &filter.project {
+        chips.push(format!("project:{}", project));
+    }
+    if let Some(branch) = &filter.branch {
+        chips.push(format!("branch:{}", branch));
+    }
+    if filter.has_todos {
+        chips.push("has-todos".to_string());
+    }
+    chips
 }
 
 fn render_plates(frame: &mut Frame, app: &App, area: Rect) {
@@ -69,7 +167,7 @@ fn render_plates(frame: &mut Frame, app: &App, area: Rect) {
     let prefix_width = if app.config.minimal_mode {
         1 + 1 + 1 + 25 + 1 // unseen + icon + space + label + space
     } else {
-        2 + num_width + 1 + 1 + 1 + 1 + 25 + 1 + 8 + 1
+        2 + num_width + 1 + 1 + 1 + 1 + 25 + 1 + 8 + 1 + USAGE_WIDTH + 1
     };
     let summary_width = (area.width as usize).saturating_sub(prefix_width).max(1);
     let mut items: Vec<ListItem> = Vec::new();
@@ -146,7 +244,12 @@ fn render_plates(frame: &mut Frame, app: &App, area: Rect) {
     }
 
     if items.is_empty() {
-        items.push(ListItem::new(Line::from("No plates")));
+        let empty = if app.search_matches.is_some() {
+            "No matching plates"
+        } else {
+            "No plates"
+        };
+        items.push(ListItem::new(Line::from(empty)));
     }
 
     let list = List::new(items);
@@ -174,15 +277,28 @@ fn render_plate_item<'a>(
     let status_color = status_color(plate.status, theme);
     let icon = plate.status.icon();
 
-    let label = format_label(plate.project_name(), plate.git_branch.as_deref());
+    let label = format_label(
+        plate.host.as_deref(),
+        plate.project_name(),
+        plate.git_branch.as_deref(),
+    );
 
     let todo = plate.todo_progress.as_deref().unwrap_or("");
     let summary = plate.summary.as_deref().unwrap_or("");
-    let full_summary = if todo.is_empty() {
+    let mut full_summary = if todo.is_empty() {
         summary.to_string()
     } else {
         format!("{} {}", todo, summary)
     };
+    // The selected row spells out what the plate is waiting to be asked
+    if is_selected {
+        for question in &plate.pending_questions {
+            if !full_summary.is_empty() {
+                full_summary.push('\n');
+            }
+            full_summary.push_str(&format!("? {}", question));
+        }
+    }
 
     let style = if is_selected {
         Style::default()
@@ -197,12 +313,13 @@ fn render_plate_item<'a>(
     } else {
         let status_short = pad_or_truncate(plate.status.short_name(), 8);
         format!(
-            "[{:>width$}]{} {} {} {}",
+            "[{:>width$}]{} {} {} {} {}",
             idx + 1,
             unseen_marker,
             icon,
             label,
             status_short,
+            format_usage(plate.tokens, plate.cost_usd),
             width = num_width,
         )
     };
@@ -257,15 +374,216 @@ fn render_plate_item<'a>(
     }
 }
 
+fn render_inbox(frame: &mut Frame, app: &App, area: Rect) {
+    let title_style = if app.inbox_focused {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().add_modifier(Modifier::DIM)
+    };
+    let title = Line::from(Span::styled(
+        format!("INBOX ({})", app.inbox.len()),
+        title_style,
+    ));
+
+    let visible = MAX_INBOX_ROWS as usize;
+    let skip = (app.inbox_index + 1).saturating_sub(visible);
+    let mut lines = vec![Line::from(""), title];
+    for (idx, item) in app.inbox.iter().enumerate().skip(skip).take(visible) {
+        let text = format!("{} {}", pad_or_truncate(&item.item_type, 10), item.title);
+        let text = pad_or_truncate(&text, area.width as usize);
+        let style = if app.inbox_focused && idx == app.inbox_index {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else if app.dispatching == Some(item.id) {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(text, style)));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// The selected plate's fields, goal, todos and latest tool calls.
+fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title(" Detail ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(plate) = app.selected_plate() else {
+        frame.render_widget(Paragraph::new("No plate selected"), inner);
+        return;
+    };
+    let detail = app
+        .detail
+        .as_ref()
+        .filter(|d| d.plate.session_id == plate.session_id);
+
+    let label = Style::default().add_modifier(Modifier::DIM);
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<9}", name), label),
+            Span::raw(value),
+        ])
+    };
+    let now = chrono::Utc::now();
+    let elapsed = |at: &str| {
+        chrono::DateTime::parse_from_rfc3339(at)
+            .map(|at| format_elapsed((now - at.with_timezone(&chrono::Utc)).num_seconds()))
+            .unwrap_or_default()
+    };
+
+    let status = match detail.and_then(|d| d.status_since.as_deref()) {
+        Some(since) => format!("{} for {}", plate.status.as_str(), elapsed(since)),
+        None => plate.status.as_str().to_string(),
+    };
+    let mut lines = vec![
+        field("Path", plate.project_path.clone()),
+        field(
+            "Branch",
+            plate.git_branch.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        field("Session", plate.session_id.clone()),
+        field(
+            "Tmux",
+            plate.tmux_target.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        field("Status", status),
+        field(
+            "Created",
+            format!(
+                "{} ({} ago)",
+                local_time(&plate.created_at),
+                elapsed(&plate.created_at)
+            ),
+        ),
+        field(
+            "Updated",
+            format!(
+                "{} ({} ago)",
+                local_time(&plate.updated_at),
+                elapsed(&plate.updated_at)
+            ),
+        ),
+    ];
+
+    let Some(detail) = detail else {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Loading...", label)));
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+        return;
+    };
+
+    if let Some(goal) = &detail.goal {
+        lines.push(field("Goal", goal.clone()));
+    }
+
+    if !detail.todos.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("TODOS", label)));
+        for todo in &detail.todos {
+            let (mark, text, style) = match todo.status.as_str() {
+                "completed" => ("[x]", &todo.content, label),
+                "in_progress" => (
+                    "[>]",
+                    todo.active_form.as_ref().unwrap_or(&todo.content),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                _ => ("[ ]", &todo.content, Style::default()),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("{} {}", mark, text),
+                style,
+            )));
+        }
+    }
+
+    if !detail.recent_tool_calls.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("RECENT TOOLS", label)));
+        for call in detail.recent_tool_calls.iter().take(DETAIL_TOOLS) {
+            let target = call
+                .tool_params
+                .as_ref()
+                .and_then(tool_target)
+                .map(|t| format!(" {}", t.lines().next().unwrap_or("")))
+                .unwrap_or_default();
+            lines.push(Line::from(vec![
+                Span::styled(format!("{} ", local_time(&call.created_at)), label),
+                Span::raw(format!(
+                    "{}{}",
+                    call.tool_name.as_deref().unwrap_or("?"),
+                    target
+                )),
+            ]));
+        }
+    }
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
+/// "45s", "12m", "3h 5m", "2d 4h".
+fn format_elapsed(secs: i64) -> String {
+    let secs = secs.max(0);
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d {}h", s / 86400, s % 86400 / 3600),
+    }
+}
+
+/// The latest entries of the selected plate's digest.
+fn render_digest(frame: &mut Frame, digest: &Digest, area: Rect) {
+    let title = Line::from(Span::styled(
+        format!("SINCE YOU LAST LOOKED ({})", digest.entries.len()),
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+    let visible = MAX_DIGEST_ROWS as usize;
+    let skip = digest.entries.len().saturating_sub(visible);
+    let mut lines = vec![Line::from(""), title];
+    for entry in digest.entries.iter().skip(skip) {
+        let text = pad_or_truncate(&entry.to_string(), area.width as usize);
+        lines.push(Line::from(text));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
 fn render_auth_banner(frame: &mut Frame, area: Rect) {
     let banner = Paragraph::new(
-        " No API key configured. Run `sp auth set` for AI summaries. Press 'd' to dismiss. ",
+        " No API key: summaries are offline guesses. Run `sp auth set` for AI summaries. Press 'd' to dismiss. ",
     )
     .style(Style::default().fg(Color::Yellow));
     frame.render_widget(banner, area);
 }
 
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
+    if let Some(message) = &app.status_message {
+        let footer = Paragraph::new(format!(" {} ", message)).style(Style::default());
+        frame.render_widget(footer, area);
+        return;
+    }
+    if let Some(item) = app.dispatching_item() {
+        let footer = Paragraph::new(format!(
+            " send \"{}\" to: choose a plate  enter:send  esc:cancel ",
+            item.title
+        ))
+        .style(Style::default().add_modifier(Modifier::BOLD));
+        frame.render_widget(footer, area);
+        return;
+    }
+    if app.inbox_focused {
+        let footer = Paragraph::new(" tab:plates  d:dispatch  x:dismiss  z:snooze 1h ")
+            .style(Style::default().add_modifier(Modifier::DIM));
+        frame.render_widget(footer, area);
+        return;
+    }
+    if app.search_editing {
+        let footer = Paragraph::new(" type to search  enter:apply  esc:clear ")
+            .style(Style::default().add_modifier(Modifier::DIM));
+        frame.render_widget(footer, area);
+        return;
+    }
+
     let on_closed_plate = app
         .selected_plate()
         .map(|p| p.status == PlateStatus::Closed)
@@ -281,10 +599,45 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
 
     let base = match enter_action {
         Some(action) => format!(
-            " esc:quit  r:refresh  s:settings  c:closed  {}  del:dismiss",
+            " esc:quit  r:refresh  s:settings  c:closed  /:search  o:sort  f:filter  {}  del:dismiss",
             action
         ),
-        None => " esc:quit  r:refresh  s:settings  c:closed  del:dismiss".to_string(),
+        None => {
+            " esc:quit  r:refresh  s:settings  c:closed  /:search  o:sort  f:filter  del:dismiss"
+                .to_string()
+        }
+    };
+    let base = if app.config.dashboard.filter.is_empty() {
+        base
+    } else {
+        format!("{}  F:clear filters", base)
+    };
+    let base = if app
+        .selected_plate()
+        .is_some_and(|p| p.transcript_path.is_some() && p.host.is_none())
+    {
+        format!("{}  t:transcript", base)
+    } else {
+        base
+    };
+    let base = format!(
+        "{}  i:{}",
+        base,
+        if app.show_detail {
+            "hide detail"
+        } else {
+            "detail"
+        }
+    );
+    let base = if app.selected_plate().is_some_and(|p| p.has_plan) {
+        format!("{}  p:plan", base)
+    } else {
+        base
+    };
+    let base = if app.inbox.is_empty() {
+        base
+    } else {
+        format!("{}  tab:inbox", base)
     };
     let text = if app.show_auth_banner {
         format!("{}  d:dismiss banner ", base)
@@ -295,6 +648,118 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(footer, area);
 }
 
+fn render_transcript(frame: &mut Frame, app: &App, view: &TranscriptView) {
+    let chunks = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .split(frame.area());
+
+    let mut spans = vec![Span::styled(
+        format!(" Transcript: {} ", view.title),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if view.follow {
+        spans.push(Span::styled(
+            " following ",
+            Style::default().fg(Color::Green),
+        ));
+    }
+    if view.show_thinking {
+        spans.push(Span::styled(
+            " thinking shown ",
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+    if let Some(search) = &view.search {
+        let cursor = if view.search_editing { "_" } else { "" };
+        spans.push(Span::raw(format!(" /{}{}", search, cursor)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), chunks[0]);
+
+    let body = chunks[1];
+    let lines = view.lines(body.width as usize);
+    let page = body.height as usize;
+    let max_scroll = lines.len().saturating_sub(page);
+    let scroll = if view.follow {
+        max_scroll
+    } else {
+        // Keep the selected item in view, showing as much of it as fits
+        let (start, len) = view
+            .layout
+            .borrow()
+            .get(view.selected)
+            .copied()
+            .unwrap_or_default();
+        let scroll = view.scroll.get();
+        let scroll = if start < scroll {
+            start
+        } else if start + len > scroll + page {
+            start.min((start + len).saturating_sub(page))
+        } else {
+            scroll
+        };
+        scroll.min(max_scroll)
+    };
+    view.scroll.set(scroll);
+    view.page.set(page);
+    let shown: Vec<Line> = lines.into_iter().skip(scroll).take(page).collect();
+    if shown.is_empty() {
+        frame.render_widget(Paragraph::new(" Nothing in this transcript yet"), body);
+    } else {
+        frame.render_widget(Paragraph::new(shown), body);
+    }
+
+    let footer = match &app.status_message {
+        Some(message) => Paragraph::new(format!(" {} ", message)),
+        None if view.search_editing => Paragraph::new(" type to search  enter:find  esc:cancel ")
+            .style(Style::default().add_modifier(Modifier::DIM)),
+        None => Paragraph::new(
+            " esc:back  j/k  space/pgup  g/G  f:follow  enter:expand  t:thinking  /:search  n/N ",
+        )
+        .style(Style::default().add_modifier(Modifier::DIM)),
+    };
+    frame.render_widget(footer, chunks[2]);
+}
+
+fn render_plan(frame: &mut Frame, view: &PlanView) {
+    let area = frame.area();
+    let width = (area.width * 9 / 10).max(20).min(area.width);
+    let height = area.height.saturating_sub(2).max(5).min(area.height);
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let modal_area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(format!(" Plan: {} ", view.title))
+        .borders(Borders::ALL);
+    let inner = block.inner(modal_area);
+    frame.render_widget(block, modal_area);
+
+    let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let lines = markdown::render(&view.plan, chunks[0].width as usize);
+    let visible = chunks[0].height as usize;
+    view.max_scroll.set(lines.len().saturating_sub(visible));
+    let scroll = view.scroll.min(view.max_scroll.get());
+    let shown: Vec<Line> = lines.into_iter().skip(scroll).take(visible).collect();
+    frame.render_widget(Paragraph::new(shown), chunks[0]);
+
+    let position = if view.max_scroll.get() > 0 {
+        format!("  {}/{}", scroll, view.max_scroll.get())
+    } else {
+        String::new()
+    };
+    let footer = Paragraph::new(format!(
+        " j/k:scroll  space/pgdn:page  g/G:top/end  esc:close{}",
+        position
+    ))
+    .style(Style::default().add_modifier(Modifier::DIM));
+    frame.render_widget(footer, chunks[1]);
+}
+
 fn render_sound_settings(frame: &mut Frame, app: &App) {
     let area = frame.area();
     let width = 50.min(area.width.saturating_sub(4));
@@ -382,6 +847,76 @@ fn render_sound_settings(frame: &mut Frame, app: &App) {
     frame.render_widget(help, chunks[1]);
 }
 
+fn render_filters(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let width = 44.min(area.width.saturating_sub(4));
+    let height = (FILTER_ROWS as u16 + 4).min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let modal_area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, modal_area);
+
+    let block = Block::default().title(" Filters ").borders(Borders::ALL);
+    let inner = block.inner(modal_area);
+    frame.render_widget(block, modal_area);
+
+    let inner_width = inner.width as usize;
+    let filter = &app.config.dashboard.filter;
+    let check = |on: bool| if on { "[x]" } else { "[ ]" };
+
+    let mut rows: Vec<(usize, String)> = PlateStatus::ALL
+        .iter()
+        .enumerate()
+        .map(|(idx, status)| {
+            let on = filter.statuses.contains(status);
+            (idx, format!("{} {}", check(on), status.short_name()))
+        })
+        .collect();
+    let statuses = PlateStatus::ALL.len();
+    rows.push((
+        statuses,
+        format!(
+            "{:12} {}",
+            "Project",
+            filter.project.as_deref().unwrap_or("any")
+        ),
+    ));
+    rows.push((
+        statuses + 1,
+        format!(
+            "{:12} {}",
+            "Branch",
+            filter.branch.as_deref().unwrap_or("any")
+        ),
+    ));
+    rows.push((
+        statuses + 2,
+        format!("{} has todos", check(filter.has_todos)),
+    ));
+
+    let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, text) in rows {
+        if idx == statuses {
+            lines.push(Line::from(""));
+        }
+        let style = if idx == app.filter_row {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        let padded = format!("{:<width$}", text, width = inner_width);
+        lines.push(Line::from(Span::styled(padded, style)));
+    }
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let help = Paragraph::new("f/esc:close  enter/arrows:change  x:clear")
+        .style(Style::default().add_modifier(Modifier::DIM));
+    frame.render_widget(help, chunks[1]);
+}
+
 fn status_color(status: PlateStatus, theme: &str) -> Color {
     match theme {
         "light" => match status {
@@ -419,11 +954,31 @@ fn pad_or_truncate(s: &str, width: usize) -> String {
     }
 }
 
-fn format_label(project: &str, branch: Option<&str>) -> String {
+/// Width of the token/cost column in explicit mode.
+const USAGE_WIDTH: usize = 13;
+
+/// "1.2M  $3.40", blank until the plate has used any tokens.
+fn format_usage(tokens: u64, cost_usd: f64) -> String {
+    if tokens == 0 {
+        return " ".repeat(USAGE_WIDTH);
+    }
+    let tokens = match tokens {
+        n if n >= 1_000_000 => format!("{:.1}M", n as f64 / 1_000_000.0),
+        n if n >= 1_000 => format!("{}k", n / 1_000),
+        n => n.to_string(),
+    };
+    format!("{:>5} {:>7}", tokens, format!("${:.2}", cost_usd))
+}
+
+fn format_label(host: Option<&str>, project: &str, branch: Option<&str>) -> String {
     let label = match branch {
         Some(b) => format!("{}/{}", project, b),
         None => project.to_string(),
     };
+    let label = match host {
+        Some(h) => format!("{}:{}", h, label),
+        None => label,
+    };
     pad_or_truncate(&label, 25)
 }
 